@@ -2,16 +2,15 @@ use std::env;
 use std::io::Write;
 mod route;
 mod service;
+#[cfg(feature = "otel")]
+mod telemetry;
 
 use actix_cors::Cors;
-use actix_web::{http, App, HttpServer};
+use actix_web::{web, App, HttpServer};
 use log::{debug, info};
 use regex::Regex;
 
-use actix_web::dev::ServiceResponse;
-use actix_web::http::header::HeaderValue;
-use actix_web::http::StatusCode;
-use actix_web::middleware::{ErrorHandlerResponse, ErrorHandlers};
+use actix_web::middleware::{from_fn, ErrorHandlers};
 use std::sync::LazyLock;
 use time::OffsetDateTime;
 
@@ -19,21 +18,12 @@ const NA: &str = "n/a";
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    env_logger::builder()
-        .format(|buf, record| {
-            writeln!(
-                buf,
-                "[{}] {}: {}",
-                OffsetDateTime::now_utc()
-                    .format(&time::format_description::well_known::Rfc3339)
-                    .unwrap_or(NA.to_string()),
-                record.level(),
-                record.args()
-            )
-        })
-        .init();
+    init_logger();
+    #[cfg(feature = "otel")]
+    let tracer_provider = telemetry::init();
     let port = env::var("SERVICE_PORT").unwrap_or_else(|_| "9012".to_string());
     info!("starting exchange service on port {port} ...");
+    actix_web::rt::spawn(service::provider::keep_warm());
 
     HttpServer::new(|| {
         let cors = Cors::permissive().allowed_origin_fn(move |origin_header, _request_head| {
@@ -43,29 +33,69 @@ async fn main() -> std::io::Result<()> {
         });
         App::new()
             .wrap(cors)
-            .wrap(ErrorHandlers::new().handler(StatusCode::INTERNAL_SERVER_ERROR, render_500))
+            .wrap(ErrorHandlers::new().default_handler(route::error::render_error))
+            .wrap(from_fn(route::metrics::track_requests))
+            .wrap(from_fn(route::request_id::propagate_request_id))
             .configure(route::routes::init_routes)
+            .default_service(web::to(route::error::unknown_route))
     })
     .bind(format!("0.0.0.0:{port}"))?
     .run()
-    .await
-}
+    .await?;
 
-fn render_500<B, E>(mut res: ServiceResponse<B>) -> Result<ErrorHandlerResponse<B>, E> {
-    let response = res.response_mut();
+    #[cfg(feature = "otel")]
+    if let Some(provider) = tracer_provider {
+        if let Err(e) = provider.shutdown() {
+            log::warn!("failed to flush the pending spans: {}", e);
+        }
+    }
+    Ok(())
+}
 
-    // Set proper content type for error response
-    response.headers_mut().insert(
-        http::header::CONTENT_TYPE,
-        HeaderValue::from_static("application/json"),
-    );
+// LOG_FORMAT=json emits one JSON object per line, plain text otherwise,
+// both carry the id of the request being served
+fn init_logger() {
+    let json = env::var("LOG_FORMAT").is_ok_and(|format| format.eq_ignore_ascii_case("json"));
+    env_logger::builder()
+        .format(move |buf, record| {
+            let timestamp = OffsetDateTime::now_utc()
+                .format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or(NA.to_string());
+            let request_id = route::request_id::current_request_id();
+            let line = if json {
+                json_log_line(&timestamp, record, request_id.as_deref())
+            } else {
+                text_log_line(&timestamp, record, request_id.as_deref())
+            };
+            writeln!(buf, "{}", line)
+        })
+        .init();
+}
 
-    // Set proper status code if not already set
-    *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+fn text_log_line(timestamp: &str, record: &log::Record, request_id: Option<&str>) -> String {
+    match request_id {
+        Some(id) => format!(
+            "[{}] {} [{}]: {}",
+            timestamp,
+            record.level(),
+            id,
+            record.args()
+        ),
+        None => format!("[{}] {}: {}", timestamp, record.level(), record.args()),
+    }
+}
 
-    // If you want to modify the body, you'll need to handle the body type appropriately
-    // This depends on your specific needs and the body type B
-    Ok(ErrorHandlerResponse::Response(res.map_into_left_body()))
+fn json_log_line(timestamp: &str, record: &log::Record, request_id: Option<&str>) -> String {
+    let mut line = serde_json::json!({
+        "timestamp": timestamp,
+        "level": record.level().as_str(),
+        "target": record.target(),
+        "message": record.args().to_string(),
+    });
+    if let Some(id) = request_id {
+        line["request_id"] = serde_json::Value::from(id);
+    }
+    line.to_string()
 }
 
 fn is_allowed_origin(origin: &str) -> bool {
@@ -91,4 +121,53 @@ mod tests {
         assert!(!is_allowed_origin("https://www.example.org"));
         assert!(!is_allowed_origin("http://127.0.0.1"));
     }
+
+    #[test]
+    fn test_text_log_line() {
+        let args = format_args!("base=\"CHF\", 31 rates");
+        let record = log::Record::builder()
+            .args(args)
+            .level(log::Level::Info)
+            .build();
+
+        assert_eq!(
+            text_log_line("2024-11-12T10:00:00Z", &record, Some("abc-123")),
+            r#"[2024-11-12T10:00:00Z] INFO [abc-123]: base="CHF", 31 rates"#
+        );
+        assert_eq!(
+            text_log_line("2024-11-12T10:00:00Z", &record, None),
+            r#"[2024-11-12T10:00:00Z] INFO: base="CHF", 31 rates"#
+        );
+    }
+
+    #[test]
+    fn test_json_log_line() {
+        let args = format_args!("base=\"CHF\", 31 rates");
+        let record = log::Record::builder()
+            .args(args)
+            .level(log::Level::Warn)
+            .target("exchange_rate_service::service::provider")
+            .build();
+
+        let line: serde_json::Value = serde_json::from_str(&json_log_line(
+            "2024-11-12T10:00:00Z",
+            &record,
+            Some("abc-123"),
+        ))
+        .unwrap();
+
+        assert_eq!(
+            line,
+            serde_json::json!({
+                "timestamp": "2024-11-12T10:00:00Z",
+                "level": "WARN",
+                "target": "exchange_rate_service::service::provider",
+                "message": "base=\"CHF\", 31 rates",
+                "request_id": "abc-123",
+            })
+        );
+        let line: serde_json::Value =
+            serde_json::from_str(&json_log_line("2024-11-12T10:00:00Z", &record, None)).unwrap();
+        assert!(line.get("request_id").is_none());
+    }
 }
@@ -0,0 +1,295 @@
+use crate::route::error::HttpError;
+use crate::route::model::{ApiError, NewRateOverride, OverrideKind, RateOverride, SymbolConflict};
+use crate::route::validation;
+use crate::service::provider::{invalidate_rates, symbol_conflicts};
+use crate::service::provider_override::{add_override, overrides, remove_override};
+use actix_web::http::header;
+use actix_web::http::StatusCode;
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse};
+use std::env;
+
+// bearer token of the admin endpoints, they are disabled when not set
+pub struct AdminConfig {
+    token: Option<String>,
+}
+
+impl AdminConfig {
+    pub fn from_env() -> Self {
+        Self::new(env::var("ADMIN_TOKEN").ok())
+    }
+
+    pub fn new(token: Option<String>) -> Self {
+        Self {
+            token: token.filter(|token| !token.trim().is_empty()),
+        }
+    }
+}
+
+fn authorize(req: &HttpRequest, token: Option<&str>) -> Result<(), HttpError> {
+    let Some(token) = token else {
+        return Err(HttpError::new(
+            StatusCode::FORBIDDEN,
+            "the admin endpoints are disabled",
+        ));
+    };
+    let bearer = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match bearer {
+        Some(bearer) if constant_time_eq(bearer.trim().as_bytes(), token.as_bytes()) => Ok(()),
+        _ => Err(HttpError::unauthorized("a valid bearer token is required")),
+    }
+}
+
+// doesn't reveal the length of the matching prefix via timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[utoipa::path(
+    get,
+    tag = "admin",
+    responses(
+        (
+        status = 200,
+        description = "Currencies named differently by the providers, with the name chosen by priority and the name of every provider",
+        body = Vec < SymbolConflict >,
+        ),
+        (status = 401, description = "Missing or invalid bearer token", body = ApiError),
+        (status = 403, description = "Admin endpoints disabled, ADMIN_TOKEN is not set", body = ApiError),
+    )
+)]
+#[get("/api/admin/symbols/conflicts")]
+#[cfg_attr(
+    feature = "otel",
+    tracing::instrument(name = "GET /api/admin/symbols/conflicts", skip_all)
+)]
+pub async fn symbols_conflicts(
+    req: HttpRequest,
+    admin: web::Data<AdminConfig>,
+) -> Result<HttpResponse, HttpError> {
+    authorize(&req, admin.token.as_deref())?;
+    Ok(HttpResponse::Ok().json(symbol_conflicts().await))
+}
+
+#[utoipa::path(
+    get,
+    tag = "admin",
+    responses(
+        (
+        status = 200,
+        description = "Pegs and fixed rates served instead of the providers, in priority order (later entries win)",
+        body = Vec < RateOverride >,
+        ),
+        (status = 401, description = "Missing or invalid bearer token", body = ApiError),
+        (status = 403, description = "Admin endpoints disabled, ADMIN_TOKEN is not set", body = ApiError),
+    )
+)]
+#[get("/api/admin/overrides")]
+#[cfg_attr(
+    feature = "otel",
+    tracing::instrument(name = "GET /api/admin/overrides", skip_all)
+)]
+pub async fn list_overrides(
+    req: HttpRequest,
+    admin: web::Data<AdminConfig>,
+) -> Result<HttpResponse, HttpError> {
+    authorize(&req, admin.token.as_deref())?;
+    Ok(HttpResponse::Ok().json(overrides()))
+}
+
+#[utoipa::path(
+    post,
+    tag = "admin",
+    request_body = NewRateOverride,
+    responses(
+        (status = 201, description = "Fixed rate of the currency pair, applies immediately", body = RateOverride),
+        (status = 400, description = "Invalid currency, rate or validity", body = ApiError),
+        (status = 401, description = "Missing or invalid bearer token", body = ApiError),
+        (status = 403, description = "Admin endpoints disabled, ADMIN_TOKEN is not set", body = ApiError),
+    )
+)]
+#[post("/api/admin/overrides")]
+#[cfg_attr(
+    feature = "otel",
+    tracing::instrument(name = "POST /api/admin/overrides", skip_all)
+)]
+pub async fn create_override(
+    req: HttpRequest,
+    admin: web::Data<AdminConfig>,
+    body: web::Json<NewRateOverride>,
+) -> Result<HttpResponse, HttpError> {
+    authorize(&req, admin.token.as_deref())?;
+    let new = validation::rate_override(body.into_inner())?;
+    let created = add_override(new);
+    invalidate_rates().await;
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(
+    delete,
+    tag = "admin",
+    params(("id" = u64, Path, description = "Id of the fixed rate", example = 2)),
+    responses(
+        (status = 204, description = "Fixed rate removed, the providers are served again"),
+        (status = 400, description = "Pegs are configured with PEGS and can't be removed", body = ApiError),
+        (status = 401, description = "Missing or invalid bearer token", body = ApiError),
+        (status = 403, description = "Admin endpoints disabled, ADMIN_TOKEN is not set", body = ApiError),
+        (status = 404, description = "Unknown id", body = ApiError),
+    )
+)]
+#[delete("/api/admin/overrides/{id}")]
+#[cfg_attr(
+    feature = "otel",
+    tracing::instrument(name = "DELETE /api/admin/overrides/{id}", skip_all)
+)]
+pub async fn delete_override(
+    req: HttpRequest,
+    admin: web::Data<AdminConfig>,
+    id: web::Path<u64>,
+) -> Result<HttpResponse, HttpError> {
+    authorize(&req, admin.token.as_deref())?;
+    let id = id.into_inner();
+    match overrides().iter().find(|o| o.id == id).map(|o| o.kind) {
+        None => Err(HttpError::not_found(format!("no override with id {}", id))),
+        Some(OverrideKind::Peg) => Err(HttpError::bad_request(format!(
+            "override {} is a peg, remove it from PEGS instead",
+            id
+        ))),
+        Some(OverrideKind::Fixed) => {
+            if remove_override(id) {
+                invalidate_rates().await;
+            }
+            Ok(HttpResponse::NoContent().finish())
+        }
+    }
+}
+
+pub fn init_routes(config: &mut web::ServiceConfig) {
+    init_routes_with(config, AdminConfig::from_env());
+}
+
+pub fn init_routes_with(config: &mut web::ServiceConfig, admin: AdminConfig) {
+    config
+        .app_data(web::Data::new(admin))
+        .service(symbols_conflicts)
+        .service(list_overrides)
+        .service(create_override)
+        .service(delete_override);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{self, TestRequest};
+    use actix_web::App;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    const TOKEN: &str = "t0ken";
+
+    fn bearer(token: &str) -> HttpRequest {
+        TestRequest::default()
+            .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+            .to_http_request()
+    }
+
+    #[test]
+    fn test_authorize() {
+        assert!(authorize(&bearer("s3cret"), Some("s3cret")).is_ok());
+
+        let error = authorize(&bearer("guess"), Some("s3cret")).unwrap_err();
+        assert_eq!(error.to_string(), "a valid bearer token is required");
+        let error = authorize(&TestRequest::default().to_http_request(), Some("s3cret"));
+        assert!(error.is_err());
+        let basic = TestRequest::default()
+            .insert_header((header::AUTHORIZATION, "Basic czNjcmV0"))
+            .to_http_request();
+        assert!(authorize(&basic, Some("s3cret")).is_err());
+    }
+
+    #[test]
+    fn test_authorize_disabled() {
+        let error = authorize(&bearer(""), None).unwrap_err();
+        assert_eq!(error.to_string(), "the admin endpoints are disabled");
+    }
+
+    #[actix_web::test]
+    async fn test_unauthorized_response() {
+        let resp = actix_web::ResponseError::error_response(&HttpError::unauthorized("no"));
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            resp.headers().get(header::WWW_AUTHENTICATE).unwrap(),
+            "Bearer"
+        );
+    }
+
+    fn admin_routes(config: &mut web::ServiceConfig) {
+        init_routes_with(config, AdminConfig::new(Some(TOKEN.to_string())));
+    }
+
+    fn authorized(req: TestRequest) -> TestRequest {
+        req.insert_header((header::AUTHORIZATION, format!("Bearer {}", TOKEN)))
+    }
+
+    #[actix_web::test]
+    async fn test_create_and_delete_override() {
+        let app = test::init_service(App::new().configure(admin_routes)).await;
+        let new = json!({"base": "xts", "counter": "XXX", "rate": 2.5, "valid_from": "2024-11-01"});
+
+        let req = TestRequest::post()
+            .uri("/api/admin/overrides")
+            .set_json(&new);
+        let resp = test::call_service(&app, authorized(req).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let created: RateOverride = test::read_body_json(resp).await;
+        assert_eq!(created.kind, OverrideKind::Fixed);
+        assert_eq!(
+            (created.base.as_str(), created.counter.as_str()),
+            ("XTS", "XXX")
+        );
+        assert_eq!(created.rate, dec!(2.5));
+        assert!(overrides().contains(&created));
+
+        let uri = format!("/api/admin/overrides/{}", created.id);
+        let resp = test::call_service(
+            &app,
+            authorized(TestRequest::delete().uri(&uri)).to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        assert!(!overrides().contains(&created));
+        let resp = test::call_service(
+            &app,
+            authorized(TestRequest::delete().uri(&uri)).to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_create_override_rejected() {
+        let app = test::init_service(App::new().configure(admin_routes)).await;
+        let twice = json!({"base": "XTS", "counter": "xts", "rate": 2.5});
+
+        let req = TestRequest::post()
+            .uri("/api/admin/overrides")
+            .set_json(&twice);
+        let resp = test::call_service(&app, authorized(req).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let req = TestRequest::post()
+            .uri("/api/admin/overrides")
+            .set_json(json!({"base": "XTS", "counter": "XXX", "rate": 2.5}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let req = TestRequest::delete()
+            .uri("/api/admin/overrides/1")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+}
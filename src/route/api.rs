@@ -1,27 +1,134 @@
-use crate::route::model::ExchangeRate;
-use crate::service::provider::{historical_rates_of, rates_of, symbols};
-use actix_web::{get, web, HttpResponse, Responder};
-use time::{Date, Duration, OffsetDateTime};
-use utoipa::OpenApi;
+use crate::route::admin;
+use crate::route::csv::{self, FormatQuery};
+use crate::route::decimal;
+use crate::route::error::HttpError;
+use crate::route::model::{
+    Aggregation, ApiError, CircuitState, CircuitStatus, Conversion, Currency, ExchangeRate, Fill,
+    FilledRate, Fluctuation, FluctuationReport, FluctuationSort, FormattedAmount, Interval,
+    LatencyPercentiles, NewRateOverride, Observation, Ohlc, OverrideKind, PeriodRate, PeriodRates,
+    ProviderFailure, ProviderStatus, ProviderSymbol, QuotaStatus, Quote, RateOverride,
+    RateStatistics, Rounding, Side, SymbolConflict,
+};
+use crate::route::validation::{self, DateRange, Pricing, SymbolsFilter};
+use crate::service::currency_names::localized_name;
+use crate::service::legacy::find_legacy;
+use crate::service::provider::{historical_rates_of, provider_statuses, rates_of, symbols};
+use crate::service::series::{
+    counter_series, fill_gaps, fluctuations, overridden_periods, resample, resample_all, statistics,
+};
+use crate::service::spread::spreads;
+use crate::service::{conversion, currency, format};
+use actix_web::http::header;
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use time::OffsetDateTime;
+use utoipa::{IntoParams, OpenApi};
 use utoipa_swagger_ui::SwaggerUi;
 
+// set on the single rate when it is pegged or overridden by an administrator
+const RATE_OVERRIDDEN_HEADER: &str = "x-rate-overridden";
+
+// the currency names by default, the ISO 4217 metadata with ?details=true
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct CurrencyDetails {
+    #[param(example = true)]
+    details: Option<bool>,
+    // de, fr, hu or en, takes precedence over the Accept-Language header
+    #[param(example = "de")]
+    lang: Option<String>,
+}
+
 #[utoipa::path(
     get,
     tag = "rates",
+    params(CurrencyDetails),
     responses(
-        (status = 200, description = "List supported currencies",
+        (status = 200, description = "List supported currencies, with their ISO 4217 metadata (`Currency`) when details are requested, names in the requested language when available",
         body = HashMap < String, String >,
-        example = json ! ({"CHF": "Swiss Franc", "USD": "U.S. Dollar", "EUR": "Euro", "KES": "Kenyan shilling"}))
+        example = json ! ({"CHF": "Swiss Franc", "USD": "U.S. Dollar", "EUR": "Euro", "KES": "Kenyan shilling"})),
+        (status = 400, description = "Unsupported language", body = ApiError)
     )
 )]
 #[get("/api/rates/currencies")]
-async fn currencies() -> impl Responder {
+#[cfg_attr(
+    feature = "otel",
+    tracing::instrument(name = "GET /api/rates/currencies", skip_all)
+)]
+async fn currencies(
+    req: HttpRequest,
+    query: web::Query<CurrencyDetails>,
+) -> Result<HttpResponse, HttpError> {
+    let language = validation::language(&req, query.lang.as_deref())?;
     let pairs = symbols().await;
+    let mut response = HttpResponse::Ok();
+    response.insert_header((header::CONTENT_LANGUAGE, language));
+    // the names depend on the Accept-Language header, caches must not share them across languages
+    response.insert_header((header::VARY, "Accept-Language"));
+    if query.details.unwrap_or_default() {
+        let described = pairs
+            .iter()
+            .map(|(k, v)| {
+                let mut described = currency::describe(k, v);
+                if let Some(name) = localized_name(k, language) {
+                    described.name = name.to_string();
+                }
+                (k.to_uppercase(), described)
+            })
+            .collect::<std::collections::BTreeMap<_, _>>();
+        return Ok(response.json(described));
+    }
+    // falling back to the English names of the providers
     let sorted = pairs
         .iter()
-        .map(|(k, v)| (k.to_uppercase(), v.clone()))
+        .map(|(k, v)| {
+            let name = localized_name(k, language).map_or_else(|| v.clone(), str::to_string);
+            (k.to_uppercase(), name)
+        })
         .collect::<std::collections::BTreeMap<_, _>>();
-    web::Json(sorted)
+    Ok(response.json(sorted))
+}
+
+#[utoipa::path(
+    get,
+    tag = "currencies",
+    params(
+        ("code" = String, Path, example = "UGX"),
+    ),
+    responses(
+        (status = 200, description = "ISO 4217 metadata of the currency, withdrawn currencies included", body = Currency),
+        (status = 400, description = "Invalid currency code", body = ApiError),
+        (status = 404, description = "Currency not in the catalogue", body = ApiError),
+    )
+)]
+#[get("/api/currencies/{code}")]
+#[cfg_attr(feature = "otel", tracing::instrument(name = "GET /api/currencies/{code}", skip_all, fields(code = %code)))]
+async fn currency_metadata(code: web::Path<String>) -> Result<HttpResponse, HttpError> {
+    // withdrawn currencies are not known by the providers, the format is checked only
+    let code = validation::currency_format(&code)?;
+    match currency::currency_info(&code) {
+        Some(info) => Ok(HttpResponse::Ok().json(Currency::from(info))),
+        None => Err(HttpError::not_found(format!(
+            "currency {} is not in the ISO 4217 catalogue",
+            code
+        ))),
+    }
+}
+
+// resampling of the historical rates, daily rates by default
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct Resampling {
+    // day (default), week or month
+    #[param(inline, example = "week")]
+    interval: Option<Interval>,
+    // last (default), mean or ohlc for open/high/low/close candles
+    #[param(inline, example = "ohlc")]
+    aggregation: Option<Aggregation>,
+    // none (default), previous or linear, fills the days without rates before resampling
+    #[param(inline, example = "previous")]
+    fill: Option<Fill>,
 }
 
 #[utoipa::path(
@@ -29,26 +136,79 @@ async fn currencies() -> impl Responder {
     tag = "rates",
     params(
         ("base" = String, Path, example = "CHF"),
+        DateRange,
+        SymbolsFilter,
+        Resampling,
+        FormatQuery,
     ),
     responses(
         (
         status = 200,
-        body = HashMap < time::Date, ExchangeRate >,
-        description = "Time series of the exchange rates, back to given days or today",
+        body = HashMap < time::Date, PeriodRates >,
+        description = "Time series of the exchange rates in the given range, the last 30 days by default, keyed by the first day of the period",
+        ),
+        (
+        status = 400,
+        description = "Unknown currency or invalid date range",
+        body = ApiError,
         )
     )
 )]
 #[get("/api/rates/historical/{base}")]
-async fn historical_rates(params: web::Path<String>) -> HttpResponse {
-    let base = params.into_inner().to_uppercase();
-    let (now, last_month) = history_range(30);
+#[cfg_attr(feature = "otel", tracing::instrument(name = "GET /api/rates/historical/{base}", skip_all, fields(base = %params)))]
+async fn historical_rates(
+    req: HttpRequest,
+    params: web::Path<String>,
+    range: web::Query<DateRange>,
+    filter: web::Query<SymbolsFilter>,
+    resampling: web::Query<Resampling>,
+    format: web::Query<FormatQuery>,
+) -> Result<HttpResponse, HttpError> {
+    let base = validation::currency(&params.into_inner()).await?;
+    let (from, to) = validation::date_range(&range, OffsetDateTime::now_utc().date())?;
+    let symbols = validation::symbols_filter(&filter).await?;
+    let as_csv = csv::wants_csv(&req, &format);
+    let columns = csv::columns(&format)?;
+    if as_csv {
+        csv::check_resampling(
+            resampling.aggregation.unwrap_or_default(),
+            resampling.fill.unwrap_or_default(),
+        )?;
+    }
+    let historical = historical_rates_of(base.clone(), from, to).await;
+    let historical = match &symbols {
+        Some(symbols) => historical
+            .into_iter()
+            .map(|(date, ex)| (date, ex.filter(symbols)))
+            .collect(),
+        None => historical,
+    };
+    let series = resample_all(
+        &base,
+        &historical,
+        (from, to),
+        resampling.fill.unwrap_or_default(),
+        resampling.interval.unwrap_or_default(),
+        resampling.aggregation.unwrap_or_default(),
+    );
+    if as_csv {
+        let rows = series
+            .iter()
+            .flat_map(|(date, period)| {
+                period.rates.iter().flat_map(|(counter, fx)| {
+                    let overridden = period.overridden.contains(counter);
+                    csv::period_row(*date, &base, counter, fx, overridden)
+                })
+            })
+            .collect();
+        return Ok(csv::csv_response(rows, &columns));
+    }
     // map keys can be String only!!! convert Date to String
-    let series = historical_rates_of(base, last_month, now)
-        .await
-        .iter()
-        .map(|(k, v)| (k.to_string(), v.clone()))
+    let series = series
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
         .collect::<std::collections::BTreeMap<_, _>>();
-    HttpResponse::Ok().json(series)
+    Ok(HttpResponse::Ok().json(series))
 }
 
 #[utoipa::path(
@@ -57,36 +217,185 @@ async fn historical_rates(params: web::Path<String>) -> HttpResponse {
     params(
         ("base" = String, Path, example = "CHF"),
         ("counter" = String, Path, example = "EUR"),
+        DateRange,
+        Resampling,
+        FormatQuery,
     ),
     responses(
         (
         status = 200,
-        body = HashMap < time::Date, f32 >,
-        description = "Time series of the exchange rate, back to given days or today",
+        body = HashMap < time::Date, PeriodRate >,
+        description = "Time series of the exchange rate in the given range, the last 30 days by default, keyed by the first day of the period, with FilledRate values when filling the gaps",
         example = json ! ({"2024-11-10": 1.1204, "2024-11-11": 1.0411, "2024-11-12": 1.0918})
+        ),
+        (
+        status = 400,
+        description = "Unknown currency or invalid date range",
+        body = ApiError,
         )
     )
 )]
 #[get("/api/rates/historical/{base}/{counter}")]
-async fn historical_rate(params: web::Path<(String, String)>) -> HttpResponse {
+#[cfg_attr(feature = "otel", tracing::instrument(name = "GET /api/rates/historical/{base}/{counter}", skip_all, fields(base = %params.0, counter = %params.1)))]
+async fn historical_rate(
+    req: HttpRequest,
+    params: web::Path<(String, String)>,
+    range: web::Query<DateRange>,
+    resampling: web::Query<Resampling>,
+    format: web::Query<FormatQuery>,
+) -> Result<HttpResponse, HttpError> {
     let (base, counter) = params.into_inner();
-    let base = base.to_uppercase();
-    let counter = counter.to_uppercase();
-    let (now, last_month) = history_range(30);
+    let base = validation::currency(&base).await?;
+    let counter = validation::currency(&counter).await?;
+    let (from, to) = validation::date_range(&range, OffsetDateTime::now_utc().date())?;
+    let as_csv = csv::wants_csv(&req, &format);
+    let columns = csv::columns(&format)?;
+    if as_csv {
+        csv::check_resampling(
+            resampling.aggregation.unwrap_or_default(),
+            resampling.fill.unwrap_or_default(),
+        )?;
+    }
+    let historical = historical_rates_of(base.clone(), from, to).await;
+    let series = counter_series(&historical, &counter);
+    let fill = resampling.fill.unwrap_or_default();
+    let interval = resampling.interval.unwrap_or_default();
+    let series = resample(
+        &fill_gaps(&series, from, to, fill),
+        interval,
+        resampling.aggregation.unwrap_or_default(),
+    );
+    if as_csv {
+        let overridden = overridden_periods(&historical, &counter, interval);
+        let rows = series
+            .iter()
+            .flat_map(|(date, period)| {
+                let overridden = overridden.contains(date);
+                csv::period_row(*date, &base, &counter, &period.rate, overridden)
+            })
+            .collect();
+        return Ok(csv::csv_response(rows, &columns));
+    }
     // map keys can be String only!!! convert Date to String
-    let series = historical_rates_of(base, last_month, now)
-        .await
-        .iter()
-        .flat_map(|(k, ex)| ex.rates.get(&counter).map(|r| (k, r)))
-        .map(|(k, v)| (k.to_string(), *v))
+    if fill == Fill::None {
+        let series = series
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.rate))
+            .collect::<std::collections::BTreeMap<_, _>>();
+        return Ok(HttpResponse::Ok().json(series));
+    }
+    // the filled points are marked
+    let series = series
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
         .collect::<std::collections::BTreeMap<_, _>>();
-    HttpResponse::Ok().json(series)
+    Ok(HttpResponse::Ok().json(series))
 }
 
-fn history_range(days: i64) -> (Date, Date) {
-    let now = OffsetDateTime::now_utc().date();
-    let last_month = now - Duration::days(days);
-    (now, last_month)
+#[utoipa::path(
+    get,
+    tag = "rates",
+    params(
+        ("base" = String, Path, example = "CHF"),
+        ("counter" = String, Path, example = "EUR"),
+        DateRange,
+    ),
+    responses(
+        (
+        status = 200,
+        body = RateStatistics,
+        description = "Statistics of the exchange rate in the given range, the last 30 days by default",
+        ),
+        (
+        status = 400,
+        description = "Unknown currency or invalid date range",
+        body = ApiError,
+        ),
+        (
+        status = 404,
+        description = "No exchange rate found in the given range",
+        body = ApiError,
+        )
+    )
+)]
+#[get("/api/rates/stats/{base}/{counter}")]
+#[cfg_attr(feature = "otel", tracing::instrument(name = "GET /api/rates/stats/{base}/{counter}", skip_all, fields(base = %params.0, counter = %params.1)))]
+async fn rate_statistics(
+    params: web::Path<(String, String)>,
+    range: web::Query<DateRange>,
+) -> Result<HttpResponse, HttpError> {
+    let (base, counter) = params.into_inner();
+    let base = validation::currency(&base).await?;
+    let counter = validation::currency(&counter).await?;
+    let (from, to) = validation::date_range(&range, OffsetDateTime::now_utc().date())?;
+    let historical = historical_rates_of(base.clone(), from, to).await;
+    let series = counter_series(&historical, &counter);
+    let overridden = overridden_periods(&historical, &counter, Interval::Day);
+    match statistics(&base, &counter, &series, &overridden) {
+        Some(stats) => Ok(HttpResponse::Ok().json(stats)),
+        None => Err(HttpError::not_found(format!(
+            "no exchange rate found for {}/{} from {} to {}",
+            base, counter, from, to
+        ))),
+    }
+}
+
+// order of the fluctuation report
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct FluctuationOrder {
+    // counter (default), change or change_pct, the biggest absolute movers first
+    #[param(inline, example = "change_pct")]
+    sort: Option<FluctuationSort>,
+}
+
+#[utoipa::path(
+    get,
+    tag = "rates",
+    params(
+        ("base" = String, Path, example = "CHF"),
+        DateRange,
+        SymbolsFilter,
+        FluctuationOrder,
+    ),
+    responses(
+        (
+        status = 200,
+        body = FluctuationReport,
+        description = "Change of every counter currency in the given range, the last 30 days by default",
+        ),
+        (
+        status = 400,
+        description = "Unknown currency, invalid date range or sort order",
+        body = ApiError,
+        )
+    )
+)]
+#[get("/api/rates/fluctuation/{base}")]
+#[cfg_attr(feature = "otel", tracing::instrument(name = "GET /api/rates/fluctuation/{base}", skip_all, fields(base = %params)))]
+async fn fluctuation(
+    params: web::Path<String>,
+    range: web::Query<DateRange>,
+    filter: web::Query<SymbolsFilter>,
+    order: web::Query<FluctuationOrder>,
+) -> Result<HttpResponse, HttpError> {
+    let base = validation::currency(&params.into_inner()).await?;
+    let (from, to) = validation::date_range(&range, OffsetDateTime::now_utc().date())?;
+    let symbols = validation::symbols_filter(&filter).await?;
+    let historical = historical_rates_of(base.clone(), from, to).await;
+    let historical = match &symbols {
+        Some(symbols) => historical
+            .into_iter()
+            .map(|(date, ex)| (date, ex.filter(symbols)))
+            .collect(),
+        None => historical,
+    };
+    Ok(HttpResponse::Ok().json(FluctuationReport {
+        base,
+        from,
+        to,
+        fluctuations: fluctuations(&historical, order.sort.unwrap_or_default()),
+    }))
 }
 
 #[utoipa::path(
@@ -94,21 +403,110 @@ fn history_range(days: i64) -> (Date, Date) {
     tag = "rates",
     params(
         ("base" = String, Path, example = "CHF"),
+        SymbolsFilter,
+        Pricing,
+        FormatQuery,
     ),
     responses(
         (
         status = 200,
-        description = "List actual exchange rates with the given base currency",
+        description = "List actual exchange rates with the given base currency, the mid rates or the bid or ask with the configured spread",
         body = ExchangeRate,
         example = json ! ({"base": "CHF", "rates": {"USD": 1.1204, "EUR": 1.0305, "JPY": 174.9}})
+        ),
+        (
+        status = 400,
+        description = "Unknown currency or client",
+        body = ApiError,
         )
     )
 )]
 #[get("/api/rates/{base}")]
-async fn rates(info: web::Path<String>) -> impl Responder {
-    let base: String = info.into_inner().to_uppercase();
+#[cfg_attr(feature = "otel", tracing::instrument(name = "GET /api/rates/{base}", skip_all, fields(base = %info)))]
+async fn rates(
+    req: HttpRequest,
+    info: web::Path<String>,
+    filter: web::Query<SymbolsFilter>,
+    pricing: web::Query<Pricing>,
+    format: web::Query<FormatQuery>,
+) -> Result<HttpResponse, HttpError> {
+    let base = validation::currency(&info.into_inner()).await?;
+    let symbols = validation::symbols_filter(&filter).await?;
+    let client = validation::client(&pricing)?;
     let exchanges = rates_of(base).await;
-    web::Json(exchanges)
+    let exchanges = match symbols {
+        Some(symbols) => exchanges.filter(&symbols),
+        None => exchanges,
+    };
+    let exchanges = spreads().price(exchanges, pricing.side.unwrap_or_default(), client);
+    if csv::wants_csv(&req, &format) {
+        let columns = csv::columns(&format)?;
+        // dated with the day of the quote, the day of the request when the provider doesn't tell
+        let today = OffsetDateTime::now_utc().date();
+        let rows = exchanges
+            .rates
+            .iter()
+            .map(|(counter, fx)| csv::CsvRow {
+                date: exchanges.date_of(counter, today),
+                base: exchanges.base.clone(),
+                counter: counter.clone(),
+                rate: *fx,
+                overridden: exchanges.overridden.contains(counter),
+            })
+            .collect();
+        return Ok(csv::csv_response(rows, &columns));
+    }
+    Ok(HttpResponse::Ok().json(exchanges))
+}
+
+#[utoipa::path(
+    get,
+    tag = "rates",
+    params(
+        ("base" = String, Path, example = "CHF"),
+        ("counter" = String, Path, example = "KES"),
+        ("client" = Option<String>, Query, description = "client with a configured spread", example = "cooperaid"),
+    ),
+    responses(
+        (
+        status = 200,
+        description = "Bid, mid and ask rates of the currency pair with the spread of the client or the currencies",
+        body = Quote,
+        ),
+        (
+        status = 400,
+        description = "Unknown currency or client",
+        body = ApiError,
+        ),
+        (
+        status = 404,
+        description = "No exchange rate found",
+        body = ApiError,
+        )
+    )
+)]
+#[get("/api/rates/quote/{base}/{counter}")]
+#[cfg_attr(feature = "otel", tracing::instrument(name = "GET /api/rates/quote/{base}/{counter}", skip_all, fields(base = %params.0, counter = %params.1)))]
+async fn quote(
+    params: web::Path<(String, String)>,
+    pricing: web::Query<Pricing>,
+) -> Result<HttpResponse, HttpError> {
+    let (base, counter) = params.into_inner();
+    let base = validation::currency(&base).await?;
+    let counter = validation::currency(&counter).await?;
+    let client = validation::client(&pricing)?;
+    let exchanges = rates_of(base.clone()).await;
+    match exchanges.rates.get(&counter) {
+        Some(fx) => {
+            let mut quote = spreads().quote(&base, &counter, *fx, client);
+            quote.overridden = exchanges.overridden.contains(&counter);
+            Ok(HttpResponse::Ok().json(quote))
+        }
+        None => Err(HttpError::not_found(format!(
+            "no exchange rate found for {}/{}",
+            base, counter
+        ))),
+    }
 }
 
 #[utoipa::path(
@@ -117,32 +515,232 @@ async fn rates(info: web::Path<String>) -> impl Responder {
     params(
         ("base" = String, Path, example = "CHF"),
         ("counter" = String, Path, example = "EUR"),
+        Pricing,
     ),
     responses(
         (
         status = 200,
         description = "Actual exchange rate for the given base and counter currencies",
-        body = f32,
+        body = f64,
+        headers(("x-rate-overridden" = bool, description = "true when the rate is pegged or overridden by an administrator")),
         example = json ! (1.0305)
         ),
         (
+        status = 400,
+        description = "Unknown currency or client",
+        body = ApiError,
+        ),
+        (
         status = 404,
-        description = "No exchange rate found"
+        description = "No exchange rate found",
+        body = ApiError,
         )
     )
 )]
 #[get("/api/rates/{base}/{counter}")]
-async fn rate(params: web::Path<(String, String)>) -> HttpResponse {
+#[cfg_attr(feature = "otel", tracing::instrument(name = "GET /api/rates/{base}/{counter}", skip_all, fields(base = %params.0, counter = %params.1)))]
+async fn rate(
+    params: web::Path<(String, String)>,
+    pricing: web::Query<Pricing>,
+) -> Result<HttpResponse, HttpError> {
     let (base, counter) = params.into_inner();
-    let base = base.to_uppercase();
-    let counter = counter.to_uppercase();
-    let exchanges = rates_of(base).await;
+    let base = validation::currency(&base).await?;
+    let counter = validation::currency(&counter).await?;
+    let client = validation::client(&pricing)?;
+    let exchanges = rates_of(base.clone()).await;
     match exchanges.rates.get(&counter) {
-        Some(fx) => HttpResponse::Ok().json(fx),
-        None => HttpResponse::NotFound().finish(),
+        Some(fx) => {
+            let price = spreads()
+                .quote(&base, &counter, *fx, client)
+                .side(pricing.side.unwrap_or_default());
+            let mut response = HttpResponse::Ok();
+            // the body is a bare number, the flag of the other models is sent as a header
+            if exchanges.overridden.contains(&counter) {
+                response.insert_header((RATE_OVERRIDDEN_HEADER, "true"));
+            }
+            Ok(response.json(decimal::Formatted(&price)))
+        }
+        None => Err(HttpError::not_found(format!(
+            "no exchange rate found for {}/{}",
+            base, counter
+        ))),
     }
 }
 
+// amount to convert and the rounding of the result
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ConversionQuery {
+    #[param(example = "1299.90")]
+    amount: String,
+    // half_even (default), half_up or cash
+    #[param(inline, example = "cash")]
+    rounding: Option<Rounding>,
+    // adds the rounded amount formatted for the locale
+    #[param(example = "de-CH")]
+    locale: Option<String>,
+    // bid, mid (default) or ask rate
+    #[param(inline, example = "bid")]
+    side: Option<Side>,
+    // client with a configured spread
+    #[param(example = "cooperaid")]
+    client: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    tag = "rates",
+    params(
+        ("base" = String, Path, example = "EUR"),
+        ("counter" = String, Path, example = "CHF"),
+        ConversionQuery,
+    ),
+    responses(
+        (
+        status = 200,
+        description = "Amount converted with the actual exchange rate, raw and rounded to the minor units of the counter currency",
+        body = Conversion,
+        ),
+        (
+        status = 400,
+        description = "Unknown currency, invalid amount or amount too large to be converted",
+        body = ApiError,
+        ),
+        (
+        status = 404,
+        description = "No exchange rate found",
+        body = ApiError,
+        )
+    )
+)]
+#[get("/api/convert/{base}/{counter}")]
+#[cfg_attr(feature = "otel", tracing::instrument(name = "GET /api/convert/{base}/{counter}", skip_all, fields(base = %params.0, counter = %params.1)))]
+async fn convert(
+    params: web::Path<(String, String)>,
+    query: web::Query<ConversionQuery>,
+) -> Result<HttpResponse, HttpError> {
+    let (base, counter) = params.into_inner();
+    let base = validation::currency(&base).await?;
+    let counter = validation::currency(&counter).await?;
+    let amount = validation::amount(&query.amount)?;
+    let locale = match &query.locale {
+        Some(tag) => Some(validation::locale(Some(tag))?),
+        None => None,
+    };
+    let pricing = Pricing {
+        side: query.side,
+        client: query.client.clone(),
+    };
+    let client = validation::client(&pricing)?;
+    let side = pricing.side.unwrap_or_default();
+    let rounding = query.rounding.unwrap_or_default();
+    let exchanges = rates_of(base.clone()).await;
+    match exchanges.rates.get(&counter) {
+        Some(fx) => {
+            let converted = match find_legacy(&base) {
+                // the euro rules divide by the conversion factor and apply the rate of the successor
+                Some(legacy) => {
+                    let successor_fx = if counter == legacy.successor {
+                        Some(Decimal::ONE)
+                    } else {
+                        let successor = rates_of(legacy.successor.to_string()).await;
+                        successor.rates.get(&counter).copied()
+                    };
+                    let successor_fx = spreads()
+                        .quote(
+                            &base,
+                            &counter,
+                            successor_fx.unwrap_or(*fx * legacy.factor),
+                            client,
+                        )
+                        .side(side);
+                    conversion::convert_legacy(legacy, &counter, amount, successor_fx, rounding)
+                }
+                None => {
+                    let fx = spreads().quote(&base, &counter, *fx, client).side(side);
+                    conversion::convert(&base, &counter, amount, fx, rounding)
+                }
+            };
+            let mut conversion = converted.ok_or_else(|| {
+                HttpError::bad_request(format!(
+                    "the amount {} is too large to be converted to {}",
+                    amount, counter
+                ))
+            })?;
+            conversion.format =
+                locale.map(|locale| format::format_amount(conversion.rounded, &counter, locale));
+            conversion.overridden = exchanges.overridden.contains(&counter);
+            Ok(HttpResponse::Ok().json(conversion))
+        }
+        None => Err(HttpError::not_found(format!(
+            "no exchange rate found for {}/{}",
+            base, counter
+        ))),
+    }
+}
+
+// amount to be formatted for a locale
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct Formatting {
+    #[param(example = "1299")]
+    amount: String,
+    #[param(example = "CHF")]
+    currency: String,
+    // BCP 47 tag, en-US by default
+    #[param(example = "de-CH")]
+    locale: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    tag = "currencies",
+    params(Formatting),
+    responses(
+        (
+        status = 200,
+        description = "Amount rounded to the minor units of the currency and formatted with the CLDR conventions of the locale",
+        body = FormattedAmount,
+        ),
+        (
+        status = 400,
+        description = "Invalid amount, currency or unsupported locale",
+        body = ApiError,
+        )
+    )
+)]
+#[get("/api/format")]
+#[cfg_attr(
+    feature = "otel",
+    tracing::instrument(name = "GET /api/format", skip_all)
+)]
+async fn format_amount(query: web::Query<Formatting>) -> Result<HttpResponse, HttpError> {
+    let amount = validation::amount(&query.amount)?;
+    let currency = validation::currency_format(&query.currency)?;
+    let locale = validation::locale(query.locale.as_deref())?;
+    Ok(HttpResponse::Ok().json(format::format_amount(amount, &currency, locale)))
+}
+
+#[utoipa::path(
+    get,
+    tag = "status",
+    responses(
+        (
+        status = 200,
+        description = "Status of the rate providers in priority order: last successful fetch, last error, latency, circuit and quota state",
+        body = Vec < ProviderStatus >,
+        )
+    )
+)]
+#[get("/api/status/providers")]
+#[cfg_attr(
+    feature = "otel",
+    tracing::instrument(name = "GET /api/status/providers")
+)]
+async fn providers_status() -> impl Responder {
+    web::Json(provider_statuses())
+}
+
 #[derive(OpenApi)]
 #[openapi(
     info(
@@ -154,23 +752,132 @@ async fn rate(params: web::Path<(String, String)>) -> HttpResponse {
     ),
     paths(
         currencies,
+        currency_metadata,
         rates,
         rate,
+        quote,
+        convert,
+        format_amount,
         historical_rates,
         historical_rate,
+        rate_statistics,
+        fluctuation,
+        providers_status,
+        admin::symbols_conflicts,
+        admin::list_overrides,
+        admin::create_override,
+        admin::delete_override,
     ),
-    components(schemas(ExchangeRate)),
+    components(schemas(
+        ExchangeRate,
+        Currency,
+        Conversion,
+        Rounding,
+        Side,
+        Quote,
+        FormattedAmount,
+        ApiError,
+        Observation,
+        RateStatistics,
+        Fluctuation,
+        FluctuationReport,
+        FluctuationSort,
+        Interval,
+        Aggregation,
+        Ohlc,
+        PeriodRate,
+        PeriodRates,
+        Fill,
+        FilledRate,
+        ProviderStatus,
+        ProviderFailure,
+        LatencyPercentiles,
+        CircuitStatus,
+        CircuitState,
+        QuotaStatus,
+        SymbolConflict,
+        ProviderSymbol,
+        RateOverride,
+        NewRateOverride,
+        OverrideKind
+    )),
     tags(
-        (name = "rates", description = "Exchange rates")
+        (name = "rates", description = "Exchange rates"),
+        (name = "currencies", description = "Currency metadata"),
+        (name = "status", description = "Service status"),
+        (name = "admin", description = "Operations, with the ADMIN_TOKEN bearer token")
     ),
 )]
 struct ApiDoc;
 
 pub fn init_routes(config: &mut web::ServiceConfig) {
     config.service(currencies);
+    config.service(currency_metadata);
     config.service(historical_rates); // must be defined earlier, otherwise path is considered as parameter (historical={base})
     config.service(historical_rate);
+    config.service(rate_statistics);
+    config.service(fluctuation); // before rate, otherwise considered as parameter (fluctuation={base})
     config.service(rates);
+    config.service(quote);
     config.service(rate);
+    config.service(convert);
+    config.service(format_amount);
+    config.service(providers_status);
     config.service(SwaggerUi::new("/docs/{_:.*}").url("/openapi.json", ApiDoc::openapi()));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+
+    #[actix_web::test]
+    async fn test_providers_status_endpoint() {
+        let app = test::init_service(App::new().configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/status/providers")
+            .to_request();
+        let statuses: Vec<ProviderStatus> = test::call_and_read_body_json(&app, req).await;
+
+        let names = statuses.iter().map(|s| s.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "Overrides",
+                "Frankfurter v2",
+                "floatrates.com",
+                "Free Exchange API"
+            ]
+        );
+        assert!(statuses
+            .iter()
+            .all(|s| s.circuit.state == CircuitState::Closed));
+    }
+
+    #[actix_web::test]
+    async fn test_currency_metadata_endpoint() {
+        let app = test::init_service(App::new().configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/currencies/khr")
+            .to_request();
+        let khr: Currency = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(khr.code, "KHR");
+        assert_eq!(khr.numeric.as_deref(), Some("116"));
+        assert_eq!(khr.symbol.as_deref(), Some("៛"));
+        assert!(khr.active);
+
+        let req = test::TestRequest::get()
+            .uri("/api/currencies/XYZ")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+
+        let req = test::TestRequest::get()
+            .uri("/api/currencies/X1")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+    }
+}
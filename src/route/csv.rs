@@ -0,0 +1,272 @@
+use crate::route::error::HttpError;
+use crate::route::model::{Aggregation, Fill, PeriodRate};
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::fmt::Write;
+use time::Date;
+use utoipa::{IntoParams, ToSchema};
+
+const CSV_CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+
+// representation of the rates, json by default
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FormatQuery {
+    // json (default) or csv, takes precedence over the Accept header
+    #[param(inline, example = "csv")]
+    pub format: Option<Format>,
+    // extra csv columns after date,base,counter,rate, only overridden is supported
+    #[param(example = "overridden")]
+    pub columns: Option<String>,
+}
+
+// optional column of the csv, the rows are date,base,counter,rate by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    // true when the rate is pegged or overridden by an administrator
+    Overridden,
+}
+
+// a single rate of the tidy csv output
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvRow {
+    pub date: Date,
+    pub base: String,
+    pub counter: String,
+    pub rate: Decimal,
+    // pegged or overridden by an administrator
+    pub overridden: bool,
+}
+
+// csv is served when asked with ?format=csv or with Accept: text/csv (not with q=0)
+pub fn wants_csv(req: &HttpRequest, query: &FormatQuery) -> bool {
+    match query.format {
+        Some(format) => format == Format::Csv,
+        None => req
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .is_some_and(|accept| accept.split(',').any(accepts_csv)),
+    }
+}
+
+// a media range like text/csv;charset=utf-8;q=0.5
+fn accepts_csv(range: &str) -> bool {
+    let mut parts = range.split(';').map(str::trim);
+    let media = parts.next().unwrap_or_default();
+    let quality = parts
+        .filter_map(|param| param.strip_prefix("q="))
+        .next()
+        .map_or(Some(1.0), |q| q.parse::<f32>().ok());
+    media.eq_ignore_ascii_case("text/csv") && quality.is_some_and(|q| q > 0.0)
+}
+
+// csv has a single observed rate per row, the candles and the filled days don't fit
+pub fn check_resampling(aggregation: Aggregation, fill: Fill) -> Result<(), HttpError> {
+    if aggregation == Aggregation::Ohlc {
+        return Err(HttpError::bad_request(
+            "the ohlc aggregation is not supported in csv, use last or mean",
+        ));
+    }
+    if fill != Fill::None {
+        return Err(HttpError::bad_request(
+            "filling the gaps is not supported in csv, the rows are the observed days",
+        ));
+    }
+    Ok(())
+}
+
+pub fn columns(query: &FormatQuery) -> Result<Vec<Column>, HttpError> {
+    query
+        .columns
+        .iter()
+        .flat_map(|columns| columns.split(','))
+        .map(|column| match column.trim() {
+            "overridden" => Ok(Column::Overridden),
+            other => Err(HttpError::bad_request(format!(
+                "unknown csv column '{}', the supported columns are overridden",
+                other
+            ))),
+        })
+        .collect()
+}
+
+pub fn period_row(
+    date: Date,
+    base: &str,
+    counter: &str,
+    rate: &PeriodRate,
+    overridden: bool,
+) -> Option<CsvRow> {
+    match rate {
+        PeriodRate::Rate(rate) => Some(CsvRow {
+            date,
+            base: base.to_string(),
+            counter: counter.to_string(),
+            rate: *rate,
+            overridden,
+        }),
+        PeriodRate::Ohlc(_) => None,
+    }
+}
+
+pub fn csv_response(rows: Vec<CsvRow>, columns: &[Column]) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(CSV_CONTENT_TYPE)
+        .body(to_csv(rows, columns))
+}
+
+// ordered by date and counter, the codes and dates never need quoting
+fn to_csv(mut rows: Vec<CsvRow>, columns: &[Column]) -> String {
+    rows.sort_by(|a, b| (a.date, &a.counter).cmp(&(b.date, &b.counter)));
+    let mut csv = String::from("date,base,counter,rate");
+    for column in columns {
+        match column {
+            Column::Overridden => csv.push_str(",overridden"),
+        }
+    }
+    csv.push('\n');
+    for row in rows {
+        let _ = write!(
+            csv,
+            "{},{},{},{}",
+            row.date, row.base, row.counter, row.rate
+        );
+        for column in columns {
+            match column {
+                Column::Overridden => {
+                    let _ = write!(csv, ",{}", row.overridden);
+                }
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test;
+    use rust_decimal_macros::dec;
+    use time::macros::date;
+
+    fn row(date: Date, counter: &str, rate: Decimal) -> CsvRow {
+        CsvRow {
+            date,
+            base: "CHF".to_string(),
+            counter: counter.to_string(),
+            rate,
+            overridden: counter == "AED",
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_wants_csv() {
+        let csv = FormatQuery {
+            format: Some(Format::Csv),
+            ..Default::default()
+        };
+        let json = FormatQuery {
+            format: Some(Format::Json),
+            ..Default::default()
+        };
+        let plain = test::TestRequest::default().to_http_request();
+        let accept_csv = test::TestRequest::default()
+            .insert_header((header::ACCEPT, "application/json;q=0.9, text/csv"))
+            .to_http_request();
+        let refuse_csv = test::TestRequest::default()
+            .insert_header((header::ACCEPT, "text/csv;q=0, application/json"))
+            .to_http_request();
+        let weighted_csv = test::TestRequest::default()
+            .insert_header((header::ACCEPT, "Text/CSV; charset=utf-8; q=0.5"))
+            .to_http_request();
+        let csv_like = test::TestRequest::default()
+            .insert_header((header::ACCEPT, "text/csvx"))
+            .to_http_request();
+
+        assert!(!wants_csv(&plain, &FormatQuery::default()));
+        assert!(!wants_csv(&refuse_csv, &FormatQuery::default()));
+        assert!(wants_csv(&weighted_csv, &FormatQuery::default()));
+        assert!(!wants_csv(&csv_like, &FormatQuery::default()));
+        assert!(wants_csv(&plain, &csv));
+        assert!(wants_csv(&accept_csv, &FormatQuery::default()));
+        // the query parameter wins
+        assert!(!wants_csv(&accept_csv, &json));
+    }
+
+    #[actix_web::test]
+    async fn test_csv_response() {
+        let rows = vec![
+            row(date!(2024 - 11 - 12), "USD", dec!(1.1204)),
+            row(date!(2024 - 11 - 11), "USD", dec!(1.12)),
+            row(date!(2024 - 11 - 12), "EUR", dec!(1.0305)),
+            row(date!(2024 - 11 - 12), "AED", dec!(3.6725)),
+        ];
+
+        let resp = csv_response(rows, &[]);
+
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            CSV_CONTENT_TYPE
+        );
+        let body = actix_web::body::to_bytes(resp.into_body()).await.unwrap();
+        assert_eq!(
+            body,
+            "date,base,counter,rate\n\
+             2024-11-11,CHF,USD,1.12\n\
+             2024-11-12,CHF,AED,3.6725\n\
+             2024-11-12,CHF,EUR,1.0305\n\
+             2024-11-12,CHF,USD,1.1204\n"
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_csv_response_overridden_column() {
+        let rows = vec![
+            row(date!(2024 - 11 - 12), "USD", dec!(1.1204)),
+            row(date!(2024 - 11 - 12), "AED", dec!(3.6725)),
+        ];
+
+        let resp = csv_response(rows, &[Column::Overridden]);
+
+        let body = actix_web::body::to_bytes(resp.into_body()).await.unwrap();
+        assert_eq!(
+            body,
+            "date,base,counter,rate,overridden\n\
+             2024-11-12,CHF,AED,3.6725,true\n\
+             2024-11-12,CHF,USD,1.1204,false\n"
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_columns() {
+        let query = |columns: &str| FormatQuery {
+            columns: Some(columns.to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(columns(&FormatQuery::default()).unwrap(), vec![]);
+        assert_eq!(
+            columns(&query("overridden")).unwrap(),
+            vec![Column::Overridden]
+        );
+        assert!(columns(&query("filled")).is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_check_resampling() {
+        assert!(check_resampling(Aggregation::Last, Fill::None).is_ok());
+        assert!(check_resampling(Aggregation::Ohlc, Fill::None).is_err());
+        assert!(check_resampling(Aggregation::Mean, Fill::Previous).is_err());
+    }
+}
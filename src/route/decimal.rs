@@ -0,0 +1,105 @@
+use rust_decimal::Decimal;
+use serde::ser::Error;
+use serde::{Serialize, Serializer};
+use std::env;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+// the rates are decimals internally, exposed as exact json numbers by default for the existing clients,
+// DECIMAL_FORMAT=string emits them as strings with all the digits delivered by the providers
+static AS_STRING: LazyLock<bool> = LazyLock::new(|| {
+    env::var("DECIMAL_FORMAT").is_ok_and(|format| format.eq_ignore_ascii_case("string"))
+});
+
+// use with #[serde(serialize_with = "decimal::serialize")]
+pub fn serialize<S: Serializer>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+    serialize_as(value, *AS_STRING, serializer)
+}
+
+// for maps of rates keyed by the counter currency
+pub fn serialize_map<'a, M, K, S>(map: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+where
+    &'a M: IntoIterator<Item = (&'a K, &'a Decimal)>,
+    K: Serialize + 'a,
+    S: Serializer,
+{
+    serializer.collect_map(map.into_iter().map(|(k, v)| (k, Formatted(v))))
+}
+
+// a single rate serialized like the fields of the models
+pub struct Formatted<'a>(pub &'a Decimal);
+
+impl Serialize for Formatted<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self.0, serializer)
+    }
+}
+
+fn serialize_as<S: Serializer>(
+    value: &Decimal,
+    as_string: bool,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let text = value.normalize().to_string();
+    if as_string {
+        serializer.serialize_str(&text)
+    } else {
+        // arbitrary precision keeps the digits of the decimal, no rounding through a double
+        let number = serde_json::Number::from_str(&text).map_err(S::Error::custom)?;
+        number.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn to_json(value: Decimal, as_string: bool) -> String {
+        let mut buf = Vec::new();
+        serialize_as(
+            &value,
+            as_string,
+            &mut serde_json::Serializer::new(&mut buf),
+        )
+        .unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_serialize_as_number() {
+        assert_eq!(to_json(dec!(4603.61), false), "4603.61");
+        assert_eq!(to_json(dec!(1.10), false), "1.1");
+        assert_eq!(to_json(dec!(0.000012345678), false), "0.000012345678");
+        assert_eq!(
+            to_json(dec!(1.234567890123456789012345678), false),
+            "1.234567890123456789012345678"
+        );
+    }
+
+    #[test]
+    fn test_serialize_as_string() {
+        assert_eq!(to_json(dec!(4603.61), true), r#""4603.61""#);
+        assert_eq!(to_json(dec!(1.10), true), r#""1.1""#);
+        assert_eq!(
+            to_json(dec!(1.0000000000000000001), true),
+            r#""1.0000000000000000001""#
+        );
+    }
+
+    #[test]
+    fn test_serialize_map() {
+        let rates = std::collections::BTreeMap::from([
+            ("EUR".to_string(), dec!(1.0305)),
+            ("UGX".to_string(), dec!(4603.61)),
+        ]);
+        let mut buf = Vec::new();
+
+        serialize_map(&rates, &mut serde_json::Serializer::new(&mut buf)).unwrap();
+
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            r#"{"EUR":1.0305,"UGX":4603.61}"#
+        );
+    }
+}
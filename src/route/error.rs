@@ -0,0 +1,228 @@
+use crate::route::model::ApiError;
+use crate::route::request_id::current_request_id;
+use actix_web::body::EitherBody;
+use actix_web::dev::ServiceResponse;
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::http::StatusCode;
+use actix_web::middleware::ErrorHandlerResponse;
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
+use log::error;
+use std::fmt;
+
+// error returned by the handlers, rendered as an ApiError body
+#[derive(Debug)]
+pub struct HttpError {
+    status: StatusCode,
+    message: String,
+    details: Option<String>,
+}
+
+impl HttpError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        HttpError {
+            status,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        HttpError::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        HttpError::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        HttpError::new(StatusCode::UNAUTHORIZED, message)
+    }
+
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl ResponseError for HttpError {
+    fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status);
+        if self.status == StatusCode::UNAUTHORIZED {
+            response.insert_header((header::WWW_AUTHENTICATE, "Bearer"));
+        }
+        response.json(api_error(
+            self.status,
+            self.message.clone(),
+            self.details.clone(),
+        ))
+    }
+}
+
+fn api_error(status: StatusCode, message: String, details: Option<String>) -> ApiError {
+    let code = status
+        .canonical_reason()
+        .unwrap_or("error")
+        .to_lowercase()
+        .replace([' ', '-'], "_");
+    ApiError {
+        code,
+        message,
+        details,
+        request_id: current_request_id(),
+    }
+}
+
+// default service, for the paths not matched by any route
+pub async fn unknown_route(req: HttpRequest) -> Result<HttpResponse, HttpError> {
+    Err(HttpError::not_found(format!(
+        "no route for {} {}",
+        req.method(),
+        req.path()
+    )))
+}
+
+// replaces the plain text or empty body of the 4xx and 5xx responses with an ApiError,
+// responses which are already json are kept as they are: the ApiError of the HttpError
+// and the bodies documented for the status, like the Readiness of the 503 of /health/ready
+pub fn render_error<B>(res: ServiceResponse<B>) -> actix_web::Result<ErrorHandlerResponse<B>> {
+    let is_json = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if is_json {
+        return Ok(ErrorHandlerResponse::Response(res.map_into_left_body()));
+    }
+
+    let status = res.status();
+    let cause = res.response().error().map(|e| e.to_string());
+    let message = if status.is_server_error() {
+        // the cause might reveal internals, it is logged only
+        if let Some(cause) = &cause {
+            error!(
+                "{} {} failed: {}",
+                res.request().method(),
+                res.request().path(),
+                cause
+            );
+        }
+        status.canonical_reason().unwrap_or("error").to_string()
+    } else {
+        cause.unwrap_or_else(|| status.canonical_reason().unwrap_or("error").to_string())
+    };
+    let body = serde_json::to_string(&api_error(status, message, None))?;
+
+    let (req, mut res) = res.into_parts();
+    res.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    let res = res.set_body(body).map_into_boxed_body();
+    let res = ServiceResponse::new(req, res).map_body(|_, body| EitherBody::right(body));
+    Ok(ErrorHandlerResponse::Response(res))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::route::request_id::{propagate_request_id, REQUEST_ID_HEADER};
+    use actix_web::middleware::{from_fn, ErrorHandlers};
+    use actix_web::{test, web, App};
+
+    async fn failing() -> HttpResponse {
+        HttpResponse::InternalServerError().body("connection refused to 10.0.0.1")
+    }
+
+    async fn not_ready() -> HttpResponse {
+        HttpResponse::ServiceUnavailable().json(serde_json::json!({"ready": false}))
+    }
+
+    async fn missing() -> Result<HttpResponse, HttpError> {
+        Err(HttpError::not_found("no exchange rate found for CHF/XYZ"))
+    }
+
+    #[actix_web::test]
+    async fn test_error_bodies() {
+        let app = test::init_service(
+            App::new()
+                .wrap(ErrorHandlers::new().default_handler(render_error))
+                .wrap(from_fn(propagate_request_id))
+                .route("/failing", web::get().to(failing))
+                .route("/missing", web::get().to(missing))
+                .route("/not_ready", web::get().to(not_ready))
+                .default_service(web::to(unknown_route)),
+        )
+        .await;
+
+        // documented json bodies are kept
+        let req = test::TestRequest::get().uri("/not_ready").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body, serde_json::json!({"ready": false}));
+
+        let req = test::TestRequest::get()
+            .uri("/failing")
+            .insert_header((REQUEST_ID_HEADER, "abc-123"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        let error: ApiError = test::read_body_json(resp).await;
+        assert_eq!(error.code, "internal_server_error");
+        assert_eq!(error.message, "Internal Server Error");
+        assert_eq!(error.request_id.as_deref(), Some("abc-123"));
+
+        let req = test::TestRequest::get().uri("/missing").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let error: ApiError = test::read_body_json(resp).await;
+        assert_eq!(error.code, "not_found");
+        assert_eq!(error.message, "no exchange rate found for CHF/XYZ");
+        assert!(error.details.is_none());
+        assert!(error.request_id.is_some());
+
+        let req = test::TestRequest::post().uri("/unknown").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let error: ApiError = test::read_body_json(resp).await;
+        assert_eq!(error.message, "no route for POST /unknown");
+    }
+
+    #[actix_web::test]
+    async fn test_extractor_errors() {
+        let app = test::init_service(
+            App::new()
+                .wrap(ErrorHandlers::new().default_handler(render_error))
+                .route(
+                    "/days/{days}",
+                    web::get().to(|days: web::Path<u32>| async move {
+                        HttpResponse::Ok().json(days.into_inner())
+                    }),
+                ),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/days/many").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let error: ApiError = test::read_body_json(resp).await;
+        assert_eq!(error.code, "not_found");
+        assert!(error.message.contains("many"), "{}", error.message);
+        assert!(error.request_id.is_none());
+    }
+}
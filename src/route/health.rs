@@ -0,0 +1,106 @@
+use crate::route::model::Readiness;
+use crate::service::provider::{is_cache_warmed, last_provider_success};
+use actix_web::{get, web, HttpResponse};
+use serde_json::json;
+use std::env;
+use std::sync::LazyLock;
+use time::{Duration, OffsetDateTime};
+
+// how long a successful provider call counts as recent
+static READINESS_MAX_AGE: LazyLock<Duration> = LazyLock::new(|| {
+    let seconds = env::var("READINESS_MAX_AGE_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(7200);
+    Duration::seconds(seconds)
+});
+
+// lightweight probes for the orchestrator, without the system info collected by the welcome page
+
+#[get("/health/live")]
+async fn live() -> HttpResponse {
+    HttpResponse::Ok().json(json!({"status": "UP"}))
+}
+
+#[get("/health/ready")]
+async fn ready() -> HttpResponse {
+    let readiness = readiness(
+        is_cache_warmed(),
+        last_provider_success(),
+        OffsetDateTime::now_utc(),
+        *READINESS_MAX_AGE,
+    );
+    if readiness.ready {
+        HttpResponse::Ok().json(readiness)
+    } else {
+        HttpResponse::ServiceUnavailable().json(readiness)
+    }
+}
+
+fn readiness(
+    cache_warmed: bool,
+    last_provider_success: Option<OffsetDateTime>,
+    now: OffsetDateTime,
+    max_age: Duration,
+) -> Readiness {
+    let recent = last_provider_success.is_some_and(|at| now - at <= max_age);
+    Readiness {
+        ready: cache_warmed && recent,
+        cache_warmed,
+        last_provider_success,
+    }
+}
+
+pub fn init_routes(config: &mut web::ServiceConfig) {
+    config.service(live);
+    config.service(ready);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use time::macros::datetime;
+
+    #[actix_web::test]
+    async fn test_live_endpoint() {
+        let app = test::init_service(App::new().configure(init_routes)).await;
+
+        let req = test::TestRequest::get().uri("/health/live").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), 200);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body, json!({"status": "UP"}));
+    }
+
+    #[actix_web::test]
+    async fn test_ready_endpoint_status_matches_body() {
+        let app = test::init_service(App::new().configure(init_routes)).await;
+
+        let req = test::TestRequest::get().uri("/health/ready").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        let status = resp.status();
+        let body: Readiness = test::read_body_json(resp).await;
+        assert_eq!(status == 200, body.ready);
+        assert!(status == 200 || status == 503);
+    }
+
+    #[actix_web::test]
+    async fn test_readiness() {
+        let now = datetime!(2024-11-12 10:00:00 UTC);
+        let max_age = Duration::hours(2);
+
+        assert!(readiness(true, Some(now - Duration::minutes(5)), now, max_age).ready);
+        // cache not warmed yet
+        assert!(!readiness(false, Some(now), now, max_age).ready);
+        // no provider succeeded so far
+        assert!(!readiness(true, None, now, max_age).ready);
+        // providers failing for too long
+        let stale = readiness(true, Some(now - Duration::hours(3)), now, max_age);
+        assert!(!stale.ready);
+        assert!(stale.cache_warmed);
+        assert_eq!(stale.last_provider_success, Some(now - Duration::hours(3)));
+    }
+}
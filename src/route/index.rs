@@ -62,7 +62,7 @@ pub async fn welcome(_: HttpRequest) -> impl Responder {
             Uptime: <i>{}</i><br/>
             OS type: <i>{} {}</i><br/>
             Used/total memory: <i>{} / {}</i><br/>
-            Providers: <i>{}</i><br/>
+            Providers: <i>{}</i> <a href="/api/status/providers">status</a><br/>
             Open API <a href="/docs/">/docs</a><br/>
         </body>
     "#,
@@ -126,6 +126,7 @@ mod tests {
         assert!(body_str.contains("OS type:"));
         assert!(body_str.contains("Used/total memory:"));
         assert!(body_str.contains("Providers:"));
+        assert!(body_str.contains(r#"<a href="/api/status/providers">status</a>"#));
         assert!(body_str.contains(r#"<a href="/docs/">/docs</a>"#));
     }
 
@@ -0,0 +1,111 @@
+use crate::service::metrics::refresh_rates_age;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{get, web, HttpResponse};
+use log::error;
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, Encoder, HistogramVec, IntCounterVec,
+    TextEncoder,
+};
+use std::sync::LazyLock;
+use std::time::Instant;
+
+static HTTP_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "http_requests_total",
+        "HTTP requests by route and status",
+        &["method", "route", "status"]
+    )
+    .unwrap()
+});
+
+static HTTP_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "http_request_duration_seconds",
+        "HTTP request latency by route",
+        &["method", "route"]
+    )
+    .unwrap()
+});
+
+// records count and latency per route pattern (not per path, to keep the label cardinality low)
+pub async fn track_requests(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let started = Instant::now();
+    let method = req.method().to_string();
+    let res = next.call(req).await?;
+    let route = res
+        .request()
+        .match_pattern()
+        .unwrap_or_else(|| "unmatched".to_string());
+    HTTP_REQUESTS
+        .with_label_values(&[method.as_str(), route.as_str(), res.status().as_str()])
+        .inc();
+    HTTP_DURATION
+        .with_label_values(&[method.as_str(), route.as_str()])
+        .observe(started.elapsed().as_secs_f64());
+    Ok(res)
+}
+
+#[get("/metrics")]
+async fn metrics() -> HttpResponse {
+    refresh_rates_age();
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    match encoder.encode(&prometheus::gather(), &mut buffer) {
+        Ok(()) => HttpResponse::Ok()
+            .content_type(encoder.format_type())
+            .body(buffer),
+        Err(e) => {
+            error!("failed to encode metrics: {}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+pub fn init_routes(config: &mut web::ServiceConfig) {
+    config.service(metrics);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::middleware::from_fn;
+    use actix_web::{test, App, HttpResponse};
+
+    #[actix_web::test]
+    async fn test_metrics_endpoint_tracks_routes() {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(track_requests))
+                .configure(init_routes)
+                .route(
+                    "/api/test/{base}",
+                    web::get().to(|| async { HttpResponse::Ok().finish() }),
+                ),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/api/test/CHF").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+        let req = test::TestRequest::get().uri("/metrics").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let content_type = resp
+            .headers()
+            .get("content-type")
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(content_type.starts_with("text/plain"));
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains(
+            r#"http_requests_total{method="GET",route="/api/test/{base}",status="200"} 1"#
+        ));
+        assert!(body.contains("http_request_duration_seconds_bucket"));
+    }
+}
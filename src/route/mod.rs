@@ -1,4 +1,12 @@
+mod admin;
 mod api;
+mod csv;
+pub mod decimal;
+pub mod error;
+mod health;
 mod index;
+pub mod metrics;
 pub mod model;
+pub mod request_id;
 pub mod routes;
+mod validation;
@@ -1,7 +1,13 @@
+use crate::route::decimal;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use time::{Date, OffsetDateTime};
 use utoipa::ToSchema;
 
+// dates are exposed as 2024-11-12
+time::serde::format_description!(iso_date, Date, "[year]-[month]-[day]");
+
 // models exposed to the public via api, be careful when changing it (and adapt up-streams)
 //
 // structure used by the public exchange rate API response
@@ -10,48 +16,546 @@ pub struct ExchangeRate {
     #[schema(example = "CHF")]
     pub base: String,
     #[schema(example = r#"{"USD": 1.0, "EUR": 0.9, "JPY": 110.5}"#)]
-    pub rates: HashMap<String, f32>,
+    #[serde(serialize_with = "decimal::serialize_map")]
+    pub rates: HashMap<String, Decimal>,
+    // counter currencies pegged or overridden by an administrator instead of quoted by a provider
+    #[schema(example = json!(["AED"]))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overridden: Vec<String>,
+    // day of the quote of the counters as delivered by the providers, the day of the request when unknown
+    #[serde(skip)]
+    pub dates: HashMap<String, Date>,
 }
 
 impl ExchangeRate {
+    // the rates of that win, the overridden counters and the dates follow the winning rates
     pub fn chain(&self, that: ExchangeRate) -> ExchangeRate {
+        let mut overridden: Vec<String> = self
+            .overridden
+            .iter()
+            .filter(|counter| !that.rates.contains_key(*counter))
+            .cloned()
+            .chain(that.overridden)
+            .collect();
+        overridden.sort();
+        overridden.dedup();
+        let dates = self
+            .dates
+            .iter()
+            .filter(|(counter, _)| !that.rates.contains_key(*counter))
+            .map(|(counter, date)| (counter.clone(), *date))
+            .chain(that.dates)
+            .collect();
         ExchangeRate {
             base: that.base,
+            overridden,
+            dates,
             rates: self.rates.clone().into_iter().chain(that.rates).collect(),
         }
     }
 
+    // day of the quote of the counter
+    pub fn date_of(&self, counter: &str, today: Date) -> Date {
+        self.dates.get(counter).copied().unwrap_or(today)
+    }
+
+    // keeps the given counter currencies only
+    pub fn filter(self, symbols: &[String]) -> ExchangeRate {
+        ExchangeRate {
+            base: self.base,
+            overridden: self
+                .overridden
+                .into_iter()
+                .filter(|counter| symbols.contains(counter))
+                .collect(),
+            dates: self
+                .dates
+                .into_iter()
+                .filter(|(counter, _)| symbols.contains(counter))
+                .collect(),
+            rates: self
+                .rates
+                .into_iter()
+                .filter(|(counter, _)| symbols.contains(counter))
+                .collect(),
+        }
+    }
+
     pub fn empty(base: &str) -> ExchangeRate {
         ExchangeRate {
             base: base.to_string(),
+            overridden: Vec::new(),
+            dates: HashMap::new(),
             rates: HashMap::new(),
         }
     }
 }
 
+// ISO 4217 metadata of a currency
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct Currency {
+    #[schema(example = "UGX")]
+    pub code: String,
+    #[schema(example = "Uganda Shilling")]
+    pub name: String,
+    // three digit ISO 4217 numeric code, missing for the currencies outside of ISO 4217
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "800")]
+    pub numeric: Option<String>,
+    // decimal places of the amounts, missing when not applicable (e.g. gold)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 0)]
+    pub minor_units: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "USh")]
+    pub symbol: Option<String>,
+    // ISO 3166 alpha-2 country codes
+    #[serde(default)]
+    #[schema(example = json!(["UG"]))]
+    pub countries: Vec<String>,
+    // false for the withdrawn currencies
+    pub active: bool,
+    // smallest coin in minor units, used by the cash rounding, e.g. 5 for CHF 0.05
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 5)]
+    pub cash_rounding: Option<u32>,
+}
+
+// rounding of the converted amounts to the minor units of the currency
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Rounding {
+    // bankers rounding, half to the nearest even digit
+    #[default]
+    HalfEven,
+    // half away from zero
+    HalfUp,
+    // half up to the smallest coin, e.g. CHF 0.05, same as half up without cash rounding
+    Cash,
+}
+
+// amount converted from the base to the counter currency
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct Conversion {
+    #[schema(example = "CHF")]
+    pub base: String,
+    #[schema(example = "EUR")]
+    pub counter: String,
+    #[serde(serialize_with = "decimal::serialize")]
+    #[schema(example = 1299)]
+    pub amount: Decimal,
+    #[serde(serialize_with = "decimal::serialize")]
+    #[schema(example = 1.0305)]
+    pub rate: Decimal,
+    // amount times rate, unrounded
+    #[serde(serialize_with = "decimal::serialize")]
+    #[schema(example = 1338.6195)]
+    pub raw: Decimal,
+    // rounded to the minor units of the counter currency, same as raw for currencies without minor units
+    #[serde(serialize_with = "decimal::serialize")]
+    #[schema(example = 1338.62)]
+    pub rounded: Decimal,
+    pub rounding: Rounding,
+    // the rounded amount formatted for the requested locale
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<FormattedAmount>,
+    // the rate is pegged or overridden by an administrator instead of quoted by a provider
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub overridden: bool,
+}
+
+// amount formatted with the CLDR conventions of a locale
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct FormattedAmount {
+    // rounded to the minor units of the currency
+    #[serde(serialize_with = "decimal::serialize")]
+    #[schema(example = 1299.0)]
+    pub amount: Decimal,
+    #[schema(example = "CHF")]
+    pub currency: String,
+    #[schema(example = "de-CH")]
+    pub locale: String,
+    // the spaces are no-break spaces
+    #[schema(example = "CHF 1’299.00")]
+    pub formatted: String,
+}
+
+// side of the quote, the mid rate is the merged rate of the providers
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Bid,
+    #[default]
+    Mid,
+    Ask,
+}
+
+// bid and ask around the mid rate of a currency pair
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct Quote {
+    #[schema(example = "CHF")]
+    pub base: String,
+    #[schema(example = "KES")]
+    pub counter: String,
+    #[serde(serialize_with = "decimal::serialize")]
+    #[schema(example = 145.1035)]
+    pub bid: Decimal,
+    #[serde(serialize_with = "decimal::serialize")]
+    #[schema(example = 146.2)]
+    pub mid: Decimal,
+    #[serde(serialize_with = "decimal::serialize")]
+    #[schema(example = 147.2965)]
+    pub ask: Decimal,
+    // distance between the bid and the ask, in percent of the mid rate
+    #[serde(serialize_with = "decimal::serialize")]
+    #[schema(example = 1.5)]
+    pub spread_pct: Decimal,
+    // the mid rate is pegged or overridden by an administrator instead of quoted by a provider
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub overridden: bool,
+}
+
+// rate of a currency pair on a given day
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct Observation {
+    #[serde(with = "iso_date")]
+    #[schema(value_type = String, example = "2024-11-12")]
+    pub date: Date,
+    #[schema(example = 1.0305)]
+    #[serde(serialize_with = "decimal::serialize")]
+    pub rate: Decimal,
+    // pegged or overridden by an administrator on that day
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub overridden: bool,
+}
+
+// statistics of the time series of a currency pair
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct RateStatistics {
+    #[schema(example = "CHF")]
+    pub base: String,
+    #[schema(example = "EUR")]
+    pub counter: String,
+    pub observations: usize,
+    pub first: Observation,
+    pub last: Observation,
+    pub min: Observation,
+    pub max: Observation,
+    #[serde(serialize_with = "decimal::serialize")]
+    pub mean: Decimal,
+    // population standard deviation
+    #[serde(serialize_with = "decimal::serialize")]
+    pub stddev: Decimal,
+    // last minus first rate
+    #[serde(serialize_with = "decimal::serialize")]
+    pub change: Decimal,
+    // change relative to the first rate, in percent
+    #[serde(serialize_with = "decimal::serialize")]
+    pub change_pct: Decimal,
+}
+
+// movement of a counter currency over a range
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct Fluctuation {
+    #[schema(example = "EUR")]
+    pub counter: String,
+    pub start: Observation,
+    pub end: Observation,
+    #[serde(serialize_with = "decimal::serialize")]
+    pub change: Decimal,
+    // in percent
+    #[serde(serialize_with = "decimal::serialize")]
+    pub change_pct: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct FluctuationReport {
+    #[schema(example = "CHF")]
+    pub base: String,
+    #[serde(with = "iso_date")]
+    #[schema(value_type = String, example = "2024-10-13")]
+    pub from: Date,
+    #[serde(with = "iso_date")]
+    #[schema(value_type = String, example = "2024-11-12")]
+    pub to: Date,
+    pub fluctuations: Vec<Fluctuation>,
+}
+
+// order of the fluctuations, the biggest movers come first when sorting by change
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FluctuationSort {
+    #[default]
+    Counter,
+    Change,
+    ChangePct,
+}
+
+// length of the periods of a resampled time series, weeks start on Monday
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Interval {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+// how the daily rates of a period are combined
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregation {
+    #[default]
+    Last,
+    Mean,
+    Ohlc,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct Ohlc {
+    #[serde(serialize_with = "decimal::serialize")]
+    pub open: Decimal,
+    #[serde(serialize_with = "decimal::serialize")]
+    pub high: Decimal,
+    #[serde(serialize_with = "decimal::serialize")]
+    pub low: Decimal,
+    #[serde(serialize_with = "decimal::serialize")]
+    pub close: Decimal,
+}
+
+// how the days without rates (weekends, holidays) are filled in a daily calendar
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Fill {
+    // observed days only
+    #[default]
+    None,
+    // the rate of the last observed day
+    Previous,
+    // interpolated between the neighbouring observed days
+    Linear,
+}
+
+// rate of a period, a candle with the ohlc aggregation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(untagged)]
+pub enum PeriodRate {
+    Rate(#[serde(serialize_with = "decimal::serialize")] Decimal),
+    Ohlc(Ohlc),
+}
+
+// rate of a period of a gap filled time series
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct FilledRate {
+    pub rate: PeriodRate,
+    // at least one day of the period was filled
+    pub filled: bool,
+}
+
+// exchange rates of a period, keyed by the first day of the period
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct PeriodRates {
+    #[schema(example = "CHF")]
+    pub base: String,
+    pub rates: BTreeMap<String, PeriodRate>,
+    // counters with filled days in the period
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schema(example = json!(["EUR"]))]
+    pub filled: Vec<String>,
+    // counters pegged or overridden by an administrator on a day of the period
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schema(example = json!(["AED"]))]
+    pub overridden: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct CircuitStatus {
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub opened_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ProviderFailure {
+    #[schema(example = "request failed: operation timed out")]
+    pub message: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub at: OffsetDateTime,
+}
+
+// calculated over the latest calls, empty until the provider is called
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct LatencyPercentiles {
+    pub samples: usize,
+    pub p50_ms: Option<u64>,
+    pub p90_ms: Option<u64>,
+    pub p99_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct QuotaStatus {
+    // the provider replied with 429 and didn't succeed since
+    pub rate_limited: bool,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub since: Option<OffsetDateTime>,
+}
+
+// status of a single rate provider, used by the on-call to see which source is failing
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ProviderStatus {
+    #[schema(example = "Frankfurter v2")]
+    pub name: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_success: Option<OffsetDateTime>,
+    pub last_error: Option<ProviderFailure>,
+    pub latency: LatencyPercentiles,
+    // number of currencies in the last successful reply
+    #[schema(example = 31)]
+    pub currencies: Option<usize>,
+    pub circuit: CircuitStatus,
+    pub quota: QuotaStatus,
+}
+
+// name of a currency as delivered by a provider
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct ProviderSymbol {
+    #[schema(example = "floatrates.com")]
+    pub provider: String,
+    #[schema(example = "U.S. Dollar")]
+    pub name: String,
+}
+
+// currency named differently by the providers
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct SymbolConflict {
+    #[schema(example = "USD")]
+    pub code: String,
+    // name of the provider with the highest priority, served on /api/rates/currencies
+    #[schema(example = "US Dollar")]
+    pub chosen: String,
+    // in priority order
+    pub names: Vec<ProviderSymbol>,
+}
+
+// pegs are configured with PEGS and can't be removed, the fixed rates are managed via the admin endpoints
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OverrideKind {
+    Peg,
+    Fixed,
+}
+
+// rate replacing the providers for a currency pair, the inverse pair is derived
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct RateOverride {
+    #[schema(example = 1)]
+    pub id: u64,
+    pub kind: OverrideKind,
+    #[schema(example = "USD")]
+    pub base: String,
+    #[schema(example = "AED")]
+    pub counter: String,
+    #[schema(example = 3.6725)]
+    #[serde(serialize_with = "decimal::serialize")]
+    pub rate: Decimal,
+    // first day the rate applies (inclusive), always when omitted
+    #[serde(
+        default,
+        with = "iso_date::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schema(value_type = Option<String>, example = "2024-11-01")]
+    pub valid_from: Option<Date>,
+    // last day the rate applies (inclusive), always when omitted
+    #[serde(
+        default,
+        with = "iso_date::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schema(value_type = Option<String>, example = "2024-11-30")]
+    pub valid_to: Option<Date>,
+}
+
+// body of a new fixed rate
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct NewRateOverride {
+    #[schema(example = "CHF")]
+    pub base: String,
+    #[schema(example = "KES")]
+    pub counter: String,
+    #[schema(example = 146.2)]
+    pub rate: Decimal,
+    #[serde(default, with = "iso_date::option")]
+    #[schema(value_type = Option<String>, example = "2024-11-01")]
+    pub valid_from: Option<Date>,
+    #[serde(default, with = "iso_date::option")]
+    #[schema(value_type = Option<String>, example = "2024-11-30")]
+    pub valid_to: Option<Date>,
+}
+
+// readiness of the service, served with 503 when not ready
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct Readiness {
+    pub ready: bool,
+    pub cache_warmed: bool,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_provider_success: Option<OffsetDateTime>,
+}
+
+// body of every 4xx and 5xx response
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ApiError {
+    // snake case reason of the status code
+    #[schema(example = "not_found")]
+    pub code: String,
+    #[schema(example = "no exchange rate found for CHF/XYZ")]
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    // same as the X-Request-Id response header, to be quoted when reporting an issue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "4f1c2a9e8b7d6c5a4f1c2a9e8b7d6c5a")]
+    pub request_id: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
-    use super::ExchangeRate;
+    use super::{ExchangeRate, Observation};
+    use rust_decimal_macros::dec;
     use std::collections::HashMap;
+    use time::macros::date;
 
     #[test]
     fn test_chain() {
         let mut rates1 = HashMap::new();
-        rates1.insert("USD".to_string(), 1.0);
-        rates1.insert("EUR".to_string(), 0.9);
+        rates1.insert("USD".to_string(), dec!(1.0));
+        rates1.insert("EUR".to_string(), dec!(0.9));
 
         let mut rates2 = HashMap::new();
-        rates2.insert("GBP".to_string(), 0.8);
-        rates2.insert("JPY".to_string(), 120.0);
-        rates2.insert("USD".to_string(), 1.1);
+        rates2.insert("GBP".to_string(), dec!(0.8));
+        rates2.insert("JPY".to_string(), dec!(120.0));
+        rates2.insert("USD".to_string(), dec!(1.1));
 
         let exchange_rate1 = ExchangeRate {
             base: "USD".to_string(),
+            overridden: Vec::new(),
+            dates: HashMap::new(),
             rates: rates1,
         };
 
         let exchange_rate2 = ExchangeRate {
             base: "USD".to_string(),
+            overridden: Vec::new(),
+            dates: HashMap::new(),
             rates: rates2,
         };
 
@@ -59,9 +563,84 @@ mod tests {
 
         assert_eq!(chained.base, "USD");
         assert_eq!(chained.rates.len(), 4);
-        assert_eq!(chained.rates.get("USD"), Some(&1.1)); // second is overriding
-        assert_eq!(chained.rates.get("EUR"), Some(&0.9));
-        assert_eq!(chained.rates.get("GBP"), Some(&0.8));
-        assert_eq!(chained.rates.get("JPY"), Some(&120.0));
+        assert_eq!(chained.rates.get("USD"), Some(&dec!(1.1))); // second is overriding
+        assert_eq!(chained.rates.get("EUR"), Some(&dec!(0.9)));
+        assert_eq!(chained.rates.get("GBP"), Some(&dec!(0.8)));
+        assert_eq!(chained.rates.get("JPY"), Some(&dec!(120.0)));
+    }
+
+    #[test]
+    fn test_chain_overridden() {
+        let provider = ExchangeRate {
+            base: "USD".to_string(),
+            overridden: Vec::new(),
+            dates: HashMap::new(),
+            rates: HashMap::from([
+                ("AED".to_string(), dec!(3.67)),
+                ("EUR".to_string(), dec!(0.9)),
+            ]),
+        };
+        let pegs = ExchangeRate {
+            base: "USD".to_string(),
+            overridden: vec!["AED".to_string()],
+            dates: HashMap::new(),
+            rates: HashMap::from([("AED".to_string(), dec!(3.6725))]),
+        };
+
+        let chained = provider.chain(pegs.clone());
+        assert_eq!(chained.rates["AED"], dec!(3.6725));
+        assert_eq!(chained.overridden, vec!["AED"]);
+        // the provider wins in the other direction
+        let chained = pegs.chain(provider);
+        assert_eq!(chained.rates["AED"], dec!(3.67));
+        assert!(chained.overridden.is_empty());
+        assert!(!serde_json::to_string(&chained)
+            .unwrap()
+            .contains("overridden"));
+    }
+
+    #[test]
+    fn test_filter() {
+        let exchange_rate = ExchangeRate {
+            base: "CHF".to_string(),
+            overridden: Vec::new(),
+            dates: HashMap::new(),
+            rates: HashMap::from([
+                ("USD".to_string(), dec!(1.1)),
+                ("EUR".to_string(), dec!(1.05)),
+                ("KES".to_string(), dec!(146.2)),
+            ]),
+        };
+
+        let filtered =
+            exchange_rate.filter(&["EUR".to_string(), "KES".to_string(), "XYZ".to_string()]);
+
+        assert_eq!(filtered.base, "CHF");
+        assert_eq!(filtered.rates.len(), 2);
+        assert_eq!(filtered.rates.get("EUR"), Some(&dec!(1.05)));
+        assert_eq!(filtered.rates.get("KES"), Some(&dec!(146.2)));
+    }
+
+    #[test]
+    fn test_observation_date_format() {
+        let observation = Observation {
+            date: date!(2024 - 11 - 02),
+            rate: dec!(1.5),
+            overridden: false,
+        };
+
+        let json = serde_json::to_string(&observation).unwrap();
+
+        assert_eq!(json, r#"{"date":"2024-11-02","rate":1.5}"#);
+        let parsed: Observation = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, observation);
+        let pegged = Observation {
+            overridden: true,
+            ..observation
+        };
+        assert_eq!(
+            serde_json::to_string(&pegged).unwrap(),
+            r#"{"date":"2024-11-02","rate":1.5,"overridden":true}"#
+        );
     }
 }
@@ -0,0 +1,119 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+// correlation id of the request being served, available to everything awaited by the handler,
+// including the provider calls, none outside of a request (e.g. cache warm-up)
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+// propagates the X-Request-Id of the caller or generates a new one, echoed in the response
+pub async fn propagate_request_id(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| is_valid_request_id(value))
+        .map(str::to_string)
+        .unwrap_or_else(generate_request_id);
+    let mut res = REQUEST_ID.scope(id.clone(), next.call(req)).await?;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        res.headers_mut()
+            .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+    Ok(res)
+}
+
+// don't trust arbitrary input to end up in the logs
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn generate_request_id() -> String {
+    format!("{:016x}{:016x}", fastrand::u64(..), fastrand::u64(..))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::middleware::from_fn;
+    use actix_web::{test, web, App, HttpResponse};
+
+    async fn echo_request_id() -> HttpResponse {
+        HttpResponse::Ok().body(current_request_id().unwrap_or_default())
+    }
+
+    #[actix_web::test]
+    async fn test_propagates_request_id() {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(propagate_request_id))
+                .route("/", web::get().to(echo_request_id)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header((REQUEST_ID_HEADER, "abc-123"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.headers().get(REQUEST_ID_HEADER).unwrap(), "abc-123");
+        assert_eq!(test::read_body(resp).await, "abc-123");
+    }
+
+    #[actix_web::test]
+    async fn test_generates_request_id() {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(propagate_request_id))
+                .route("/", web::get().to(echo_request_id)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header((REQUEST_ID_HEADER, "not valid; rm -rf"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        let id = resp
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(id.len(), 32);
+        assert_eq!(test::read_body(resp).await, id);
+    }
+
+    #[actix_web::test]
+    async fn test_no_request_id_outside_of_request() {
+        assert_eq!(current_request_id(), None);
+    }
+
+    #[actix_web::test]
+    async fn test_valid_request_id() {
+        assert!(is_valid_request_id("4bf92f3577b34da6a3ce929d0e0e4736"));
+        assert!(is_valid_request_id("req_1.2-3"));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("line\nbreak"));
+        assert!(!is_valid_request_id(&"x".repeat(129)));
+    }
+}
@@ -1,10 +1,16 @@
+use crate::route::admin;
 use crate::route::api;
+use crate::route::health;
 use crate::route::index;
+use crate::route::metrics;
 
 use actix_web::web;
 
 pub fn init_routes(config: &mut web::ServiceConfig) {
     index::init_routes(config);
+    health::init_routes(config);
     api::init_routes(config);
+    admin::init_routes(config);
+    metrics::init_routes(config);
     config.service(actix_files::Files::new("/static", "static"));
 }
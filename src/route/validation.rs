@@ -0,0 +1,460 @@
+use crate::route::error::HttpError;
+use crate::route::model::{NewRateOverride, Side};
+use crate::service::basket::find_basket;
+use crate::service::currency_names::{supported_language, LANGUAGES};
+use crate::service::format::{find_locale, supported_locales, Locale};
+use crate::service::legacy::find_legacy;
+use crate::service::provider::provider_symbols;
+use crate::service::spread::spreads;
+use actix_web::http::header;
+use actix_web::HttpRequest;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use time::macros::format_description;
+use time::{Date, Duration};
+use utoipa::IntoParams;
+
+// default length of the time series when no range is given
+const DEFAULT_HISTORY_DAYS: i64 = 30;
+// longest time series to be requested from the providers at once
+const MAX_HISTORY_DAYS: i64 = 366;
+// locale of the formatted amounts when not given
+const DEFAULT_LOCALE: &str = "en-US";
+// names of the providers
+const DEFAULT_LANGUAGE: &str = "en";
+
+// optional range of the historical endpoints, the last 30 days when omitted
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DateRange {
+    // first day of the time series, 30 days before `to` by default
+    #[param(example = "2024-11-01")]
+    pub from: Option<String>,
+    // last day of the time series, today by default
+    #[param(example = "2024-11-30")]
+    pub to: Option<String>,
+}
+
+// optional filter of the counter currencies, all of them when omitted
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SymbolsFilter {
+    // comma separated counter currencies
+    #[param(example = "EUR,USD,KES")]
+    pub symbols: Option<String>,
+}
+
+// the path parameters end up in the provider urls, only ISO 4217 codes known by the providers are accepted
+pub async fn currency(code: &str) -> Result<String, HttpError> {
+    let code = currency_format(code)?;
+    known_currency(code, &provider_symbols().await)
+}
+
+// validated counter currencies of the filter, none when not filtering
+pub async fn symbols_filter(filter: &SymbolsFilter) -> Result<Option<Vec<String>>, HttpError> {
+    let codes = match symbols_format(filter)? {
+        Some(codes) => codes,
+        None => return Ok(None),
+    };
+    let symbols = provider_symbols().await;
+    codes
+        .into_iter()
+        .map(|code| known_currency(code, &symbols))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+fn symbols_format(filter: &SymbolsFilter) -> Result<Option<Vec<String>>, HttpError> {
+    match &filter.symbols {
+        Some(symbols) => symbols
+            .split(',')
+            .map(|code| currency_format(code.trim()))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some),
+        None => Ok(None),
+    }
+}
+
+// checked before uppercasing, non ASCII letters like ß would turn into valid codes
+pub fn currency_format(code: &str) -> Result<String, HttpError> {
+    if code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(code.to_ascii_uppercase())
+    } else {
+        Err(HttpError::bad_request(format!(
+            "invalid currency code '{}', expected a three letter ISO 4217 code like CHF",
+            code
+        )))
+    }
+}
+
+// the baskets and legacy currencies are always known, when the symbols of the providers are not available
+// (all providers are down) the format check has to do
+fn known_currency(code: String, symbols: &HashMap<String, String>) -> Result<String, HttpError> {
+    let derived = find_basket(&code).is_some() || find_legacy(&code).is_some();
+    if derived || symbols.is_empty() || symbols.keys().any(|k| k.eq_ignore_ascii_case(&code)) {
+        Ok(code)
+    } else {
+        Err(
+            HttpError::bad_request(format!("unknown currency code {}", code))
+                .with_details("the supported currencies are listed on /api/rates/currencies"),
+        )
+    }
+}
+
+// the amount to convert, a plain decimal number like 1299.90
+pub fn amount(value: &str) -> Result<Decimal, HttpError> {
+    Decimal::from_str_exact(value.trim()).map_err(|_| {
+        HttpError::bad_request(format!(
+            "invalid amount '{}', expected a decimal number like 1299.90",
+            value
+        ))
+    })
+}
+
+// locale of the formatted amounts, en-US by default
+pub fn locale(tag: Option<&str>) -> Result<&'static Locale, HttpError> {
+    let tag = tag.unwrap_or(DEFAULT_LOCALE);
+    find_locale(tag).ok_or_else(|| {
+        HttpError::bad_request(format!("unsupported locale '{}'", tag)).with_details(format!(
+            "the supported locales are {}",
+            supported_locales().join(", ")
+        ))
+    })
+}
+
+// language of the currency names, the query parameter wins over the Accept-Language header,
+// English when none of the accepted languages is supported
+pub fn language(req: &HttpRequest, lang: Option<&str>) -> Result<&'static str, HttpError> {
+    if let Some(lang) = lang {
+        return supported_language(lang).ok_or_else(|| {
+            HttpError::bad_request(format!("unsupported language '{}'", lang)).with_details(
+                format!("the supported languages are en, {}", LANGUAGES.join(", ")),
+            )
+        });
+    }
+    let accepted = req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|accept| accept.to_str().ok())
+        .unwrap_or_default();
+    Ok(accepted_languages(accepted)
+        .into_iter()
+        .find_map(supported_language)
+        .unwrap_or(DEFAULT_LANGUAGE))
+}
+
+// languages of the header ordered by their quality, e.g. fr-CH, fr;q=0.9, en;q=0.8
+fn accepted_languages(accept: &str) -> Vec<&str> {
+    let mut languages = accept
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((tag, quality))
+        })
+        .collect::<Vec<_>>();
+    // stable, keeps the order of the same quality
+    languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+    languages.into_iter().map(|(tag, _)| tag).collect()
+}
+
+// side of the rates and the client of the spread, see service::spread
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct Pricing {
+    // bid, mid (default) or ask
+    #[param(inline, example = "ask")]
+    pub side: Option<Side>,
+    // client with a configured spread, the spread of the currencies otherwise
+    #[param(example = "cooperaid")]
+    pub client: Option<String>,
+}
+
+pub fn client(pricing: &Pricing) -> Result<Option<&str>, HttpError> {
+    match pricing.client.as_deref() {
+        Some(client) if !spreads().knows_client(client) => Err(HttpError::bad_request(format!(
+            "unknown client '{}', no spread is configured for it",
+            client
+        ))),
+        client => Ok(client),
+    }
+}
+
+// normalized fixed rate, the currencies don't need to be known by the providers
+pub fn rate_override(new: NewRateOverride) -> Result<NewRateOverride, HttpError> {
+    let base = currency_format(&new.base)?;
+    let counter = currency_format(&new.counter)?;
+    if base == counter {
+        return Err(HttpError::bad_request(format!(
+            "the base and counter currencies must differ, got {} twice",
+            base
+        )));
+    }
+    if new.rate <= Decimal::ZERO {
+        return Err(HttpError::bad_request(format!(
+            "invalid rate {}, expected a positive number",
+            new.rate
+        )));
+    }
+    if let (Some(from), Some(to)) = (new.valid_from, new.valid_to) {
+        if from > to {
+            return Err(HttpError::bad_request(format!(
+                "valid_from {} must not be after valid_to {}",
+                from, to
+            )));
+        }
+    }
+    Ok(NewRateOverride {
+        base,
+        counter,
+        ..new
+    })
+}
+
+// validated first and last day (inclusive) of the requested time series
+pub fn date_range(range: &DateRange, today: Date) -> Result<(Date, Date), HttpError> {
+    let to = match &range.to {
+        Some(to) => parse_date("to", to)?,
+        None => today,
+    };
+    let from = match &range.from {
+        Some(from) => parse_date("from", from)?,
+        None => to - Duration::days(DEFAULT_HISTORY_DAYS),
+    };
+    if from > to {
+        return Err(HttpError::bad_request(format!(
+            "from {} must not be after to {}",
+            from, to
+        )));
+    }
+    if to > today {
+        return Err(HttpError::bad_request(format!(
+            "to {} must not be in the future",
+            to
+        )));
+    }
+    // both days are included
+    if to - from >= Duration::days(MAX_HISTORY_DAYS) {
+        return Err(HttpError::bad_request(format!(
+            "the range from {} to {} is longer than {} days",
+            from, to, MAX_HISTORY_DAYS
+        )));
+    }
+    Ok((from, to))
+}
+
+fn parse_date(name: &str, value: &str) -> Result<Date, HttpError> {
+    Date::parse(value, format_description!("[year]-[month]-[day]")).map_err(|_| {
+        HttpError::bad_request(format!(
+            "invalid {} date '{}', expected YYYY-MM-DD",
+            name, value
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use time::macros::date;
+
+    const TODAY: Date = date!(2024 - 11 - 12);
+
+    fn range(from: Option<&str>, to: Option<&str>) -> DateRange {
+        DateRange {
+            from: from.map(str::to_string),
+            to: to.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_currency_format() {
+        assert_eq!(currency_format("chf").unwrap(), "CHF");
+        assert!(currency_format("CH").is_err());
+        assert!(currency_format("CHFX").is_err());
+        assert!(currency_format("C1F").is_err());
+        assert!(currency_format("CHF&from=2020-01-01").is_err());
+        assert!(currency_format("ÄÖÜ").is_err());
+        let error = currency_format("ßa").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid currency code 'ßa', expected a three letter ISO 4217 code like CHF"
+        );
+    }
+
+    #[test]
+    fn test_symbols_format() {
+        let filter = |symbols: Option<&str>| {
+            symbols_format(&SymbolsFilter {
+                symbols: symbols.map(str::to_string),
+            })
+        };
+
+        assert_eq!(filter(None).unwrap(), None);
+        assert_eq!(
+            filter(Some("eur, USD,KES")).unwrap(),
+            Some(vec![
+                "EUR".to_string(),
+                "USD".to_string(),
+                "KES".to_string()
+            ])
+        );
+        assert!(filter(Some("EUR,,USD")).is_err());
+        assert!(filter(Some("")).is_err());
+    }
+
+    #[test]
+    fn test_known_currency() {
+        let symbols = HashMap::from([
+            ("chf".to_string(), "Swiss Franc".to_string()),
+            ("EUR".to_string(), "Euro".to_string()),
+        ]);
+
+        assert_eq!(known_currency("CHF".to_string(), &symbols).unwrap(), "CHF");
+        assert_eq!(known_currency("EUR".to_string(), &symbols).unwrap(), "EUR");
+        let error = known_currency("XYZ".to_string(), &symbols).unwrap_err();
+        assert_eq!(error.to_string(), "unknown currency code XYZ");
+        // providers are down, can't tell
+        assert!(known_currency("XYZ".to_string(), &HashMap::new()).is_ok());
+        // known without the providers
+        assert!(known_currency("DEM".to_string(), &symbols).is_ok());
+    }
+
+    #[test]
+    fn test_amount() {
+        assert_eq!(amount("1299.90").unwrap().to_string(), "1299.90");
+        assert_eq!(amount(" 12 ").unwrap().to_string(), "12");
+        assert!(amount("1'299.90").is_err());
+        assert!(amount("12,5").is_err());
+        assert!(amount("").is_err());
+    }
+
+    #[test]
+    fn test_locale() {
+        assert_eq!(locale(None).unwrap().tag, "en-US");
+        assert_eq!(locale(Some("de_ch")).unwrap().tag, "de-CH");
+        let error = locale(Some("xx")).unwrap_err();
+        assert_eq!(error.to_string(), "unsupported locale 'xx'");
+    }
+
+    #[test]
+    fn test_language() {
+        let accepting = |accept: &str| {
+            TestRequest::default()
+                .insert_header((header::ACCEPT_LANGUAGE, accept))
+                .to_http_request()
+        };
+        let plain = TestRequest::default().to_http_request();
+
+        assert_eq!(language(&plain, None).unwrap(), "en");
+        assert_eq!(language(&plain, Some("hu")).unwrap(), "hu");
+        assert!(language(&plain, Some("it")).is_err());
+        assert_eq!(language(&accepting("de-CH"), None).unwrap(), "de");
+        assert_eq!(
+            language(&accepting("it-CH, fr;q=0.8, de;q=0.9"), None).unwrap(),
+            "de"
+        );
+        assert_eq!(language(&accepting("it, *;q=0.5"), None).unwrap(), "en");
+        // the query parameter wins
+        assert_eq!(language(&accepting("de"), Some("fr")).unwrap(), "fr");
+    }
+
+    fn new_override(base: &str, counter: &str, rate: Decimal) -> NewRateOverride {
+        NewRateOverride {
+            base: base.to_string(),
+            counter: counter.to_string(),
+            rate,
+            valid_from: None,
+            valid_to: None,
+        }
+    }
+
+    #[test]
+    fn test_rate_override() {
+        let valid = rate_override(new_override("chf", "kes", Decimal::new(1462, 1))).unwrap();
+        assert_eq!(
+            (valid.base.as_str(), valid.counter.as_str()),
+            ("CHF", "KES")
+        );
+
+        assert!(rate_override(new_override("CHF", "chf", Decimal::ONE)).is_err());
+        assert!(rate_override(new_override("CHF", "KES", Decimal::ZERO)).is_err());
+        assert!(rate_override(new_override("CHFX", "KES", Decimal::ONE)).is_err());
+        let reversed = NewRateOverride {
+            valid_from: Some(date!(2024 - 11 - 30)),
+            valid_to: Some(date!(2024 - 11 - 01)),
+            ..new_override("CHF", "KES", Decimal::ONE)
+        };
+        assert_eq!(
+            rate_override(reversed).unwrap_err().to_string(),
+            "valid_from 2024-11-30 must not be after valid_to 2024-11-01"
+        );
+    }
+
+    #[test]
+    fn test_default_date_range() {
+        assert_eq!(
+            date_range(&DateRange::default(), TODAY).unwrap(),
+            (date!(2024 - 10 - 13), TODAY)
+        );
+        assert_eq!(
+            date_range(&range(None, Some("2024-02-29")), TODAY).unwrap(),
+            (date!(2024 - 01 - 30), date!(2024 - 02 - 29))
+        );
+    }
+
+    #[test]
+    fn test_date_range() {
+        assert_eq!(
+            date_range(&range(Some("2024-11-01"), Some("2024-11-12")), TODAY).unwrap(),
+            (date!(2024 - 11 - 01), TODAY)
+        );
+        assert_eq!(
+            date_range(&range(Some("2023-11-13"), None), TODAY).unwrap(),
+            (date!(2023 - 11 - 13), TODAY)
+        );
+    }
+
+    #[test]
+    fn test_date_range_boundary() {
+        // 366 days including the first and the last one
+        assert!(date_range(&range(Some("2023-11-13"), None), TODAY).is_ok());
+        assert_eq!(
+            date_range(&range(Some("2023-11-12"), None), TODAY)
+                .unwrap_err()
+                .to_string(),
+            "the range from 2023-11-12 to 2024-11-12 is longer than 366 days"
+        );
+    }
+
+    #[test]
+    fn test_invalid_date_range() {
+        let error = |from, to| date_range(&range(from, to), TODAY).unwrap_err().to_string();
+
+        assert_eq!(
+            error(Some("2024-13-01"), None),
+            "invalid from date '2024-13-01', expected YYYY-MM-DD"
+        );
+        assert_eq!(
+            error(None, Some("yesterday")),
+            "invalid to date 'yesterday', expected YYYY-MM-DD"
+        );
+        assert_eq!(
+            error(Some("2024-11-10"), Some("2024-11-01")),
+            "from 2024-11-10 must not be after to 2024-11-01"
+        );
+        assert_eq!(
+            error(None, Some("2024-11-13")),
+            "to 2024-11-13 must not be in the future"
+        );
+        assert_eq!(
+            error(Some("2023-11-01"), None),
+            "the range from 2023-11-01 to 2024-11-12 is longer than 366 days"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use crate::route::model::ExchangeRate;
+use crate::service::legacy::find_legacy;
+use log::warn;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::env;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+// composite unit like the IMF SDR, 1 basket is the sum of fixed amounts of its components
+#[derive(Debug, Clone, PartialEq)]
+pub struct Basket {
+    pub code: String,
+    // currency and amount in the order of the configuration, the first one anchors the rates of the basket
+    components: Vec<(String, Decimal)>,
+}
+
+static BASKETS: LazyLock<Vec<Basket>> =
+    LazyLock::new(|| parse_baskets(env::var("BASKETS").ok().as_deref()));
+
+pub fn baskets() -> &'static [Basket] {
+    &BASKETS
+}
+
+pub fn find_basket(code: &str) -> Option<&'static Basket> {
+    BASKETS.iter().find(|b| b.code.eq_ignore_ascii_case(code))
+}
+
+impl Basket {
+    pub fn anchor(&self) -> &str {
+        &self.components[0].0
+    }
+
+    pub fn name(&self) -> String {
+        let codes = self
+            .components
+            .iter()
+            .map(|(code, _)| code.as_str())
+            .collect::<Vec<_>>();
+        format!("Basket of {}", codes.join(", "))
+    }
+
+    // value of 1 basket in the base of the rates, none when a component is not quoted
+    fn value_in(&self, rates: &ExchangeRate) -> Option<Decimal> {
+        self.components
+            .iter()
+            .map(|(code, amount)| {
+                if *code == rates.base {
+                    Some(*amount)
+                } else {
+                    rates
+                        .rates
+                        .get(code)
+                        .filter(|rate| !rate.is_zero())
+                        .map(|rate| amount / rate)
+                }
+            })
+            .sum()
+    }
+}
+
+// adds the rates of the baskets as counters of the base currency, with all the digits as rounding
+// would wipe out the small rates
+pub fn with_baskets(rates: ExchangeRate, baskets: &[Basket]) -> ExchangeRate {
+    let mut rates = rates;
+    let computed = baskets
+        .iter()
+        .filter(|b| b.code != rates.base)
+        .filter_map(|b| {
+            let value = b.value_in(&rates)?;
+            Some((b.code.clone(), Decimal::ONE / value))
+        })
+        .collect::<Vec<_>>();
+    rates.rates.extend(computed);
+    rates
+}
+
+// rates of the basket as base, derived from the rates of its anchor currency
+pub fn basket_rates(basket: &Basket, anchor: ExchangeRate, baskets: &[Basket]) -> ExchangeRate {
+    let Some(value) = basket.value_in(&anchor) else {
+        return ExchangeRate::empty(&basket.code);
+    };
+    let anchor = with_baskets(anchor, baskets);
+    let mut rates: HashMap<String, Decimal> = anchor
+        .rates
+        .into_iter()
+        .filter(|(counter, _)| *counter != basket.code)
+        .map(|(counter, rate)| (counter, value * rate))
+        .collect();
+    rates.insert(anchor.base, value);
+    ExchangeRate {
+        base: basket.code.clone(),
+        rates,
+        overridden: anchor.overridden,
+        dates: anchor.dates,
+    }
+}
+
+// given as XDR=USD:0.57813+EUR:0.37379,VCB=EUR:0.4+USD:0.35+CHF:0.3, invalid baskets are skipped
+fn parse_baskets(baskets: Option<&str>) -> Vec<Basket> {
+    baskets
+        .unwrap_or_default()
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .filter_map(|entry| {
+            let basket = parse_basket(entry);
+            if basket.is_none() {
+                warn!(
+                    "BASKETS: invalid entry '{}', expected CODE=CUR:amount+CUR:amount",
+                    entry
+                );
+            }
+            basket
+        })
+        .collect()
+}
+
+// legacy codes are served with their conversion factor and can't be redefined
+fn parse_basket(entry: &str) -> Option<Basket> {
+    let (code, components) = entry.split_once('=')?;
+    let code = currency_code(code).filter(|code| find_legacy(code).is_none())?;
+    let components = components
+        .split('+')
+        .map(|component| {
+            let (currency, amount) = component.split_once(':')?;
+            let amount = Decimal::from_str(amount.trim()).ok()?;
+            (amount > Decimal::ZERO).then_some((currency_code(currency)?, amount))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let valid = !components.is_empty() && components.iter().all(|(c, _)| *c != code);
+    valid.then_some(Basket { code, components })
+}
+
+fn currency_code(code: &str) -> Option<String> {
+    let code = code.trim().to_uppercase();
+    (code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase())).then_some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn configured() -> Vec<Basket> {
+        parse_baskets(Some(
+            "vcb=EUR:0.4+USD:0.35+CHF:0.3, XYZ=EUR:-1,ABC=ABC:1,DEF,DEM=USD:1",
+        ))
+    }
+
+    fn eur() -> ExchangeRate {
+        ExchangeRate {
+            base: "EUR".to_string(),
+            overridden: Vec::new(),
+            dates: HashMap::new(),
+            rates: HashMap::from([
+                ("USD".to_string(), dec!(1.05)),
+                ("CHF".to_string(), dec!(0.95)),
+                ("KES".to_string(), dec!(136)),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_parse_baskets() {
+        let baskets = configured();
+
+        assert_eq!(baskets.len(), 1);
+        assert_eq!(baskets[0].code, "VCB");
+        assert_eq!(baskets[0].anchor(), "EUR");
+        assert_eq!(baskets[0].name(), "Basket of EUR, USD, CHF");
+        assert!(parse_baskets(None).is_empty());
+        // a legacy currency
+        assert!(parse_basket("DEM=USD:1").is_none());
+    }
+
+    #[test]
+    fn test_with_baskets() {
+        let rates = with_baskets(eur(), &configured());
+
+        // 0.4 + 0.35 / 1.05 + 0.3 / 0.95 EUR
+        assert_eq!(rates.rates["VCB"], dec!(0.9531772575250836120401337793));
+        assert_eq!(rates.rates["KES"], dec!(136));
+
+        let missing = ExchangeRate::empty("EUR");
+        assert!(with_baskets(missing, &configured()).rates.is_empty());
+    }
+
+    #[test]
+    fn test_basket_rates() {
+        let baskets = configured();
+
+        let rates = basket_rates(&baskets[0], eur(), &baskets);
+
+        assert_eq!(rates.base, "VCB");
+        assert_eq!(rates.rates["EUR"], dec!(1.0491228070175438596491228070));
+        assert_eq!(rates.rates["KES"], dec!(142.68070175438596491228070175));
+        assert!(!rates.rates.contains_key("VCB"));
+        let empty = basket_rates(&baskets[0], ExchangeRate::empty("EUR"), &baskets);
+        assert!(empty.rates.is_empty());
+    }
+}
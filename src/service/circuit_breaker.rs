@@ -0,0 +1,217 @@
+use crate::route::model::{CircuitState, CircuitStatus};
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use time::OffsetDateTime;
+
+// opens after a number of consecutive failures, so a provider which is down is skipped
+// instead of waiting for its timeout on every cache miss,
+// after the cooldown a single probe call is let through (half-open) to decide whether to close again
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    opened_at_utc: Option<OffsetDateTime>,
+    probe_started: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            inner: Mutex::new(Inner {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                opened_at_utc: None,
+                probe_started: None,
+            }),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let failure_threshold = env::var("CIRCUIT_FAILURE_THRESHOLD")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(3);
+        let cooldown = env::var("CIRCUIT_COOLDOWN_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(60));
+        CircuitBreaker::new(failure_threshold, cooldown)
+    }
+
+    // whether the call is allowed to reach the provider
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => {
+                let cooled_down = inner
+                    .opened_at
+                    .is_none_or(|opened_at| now.duration_since(opened_at) >= self.cooldown);
+                if cooled_down {
+                    inner.state = CircuitState::HalfOpen;
+                    inner.probe_started = Some(now);
+                }
+                cooled_down
+            }
+            CircuitState::HalfOpen => {
+                // only one probe at a time, unless the previous probe never reported back
+                let stale_probe = inner
+                    .probe_started
+                    .is_none_or(|started| now.duration_since(started) >= self.cooldown);
+                if stale_probe {
+                    inner.probe_started = Some(now);
+                }
+                stale_probe
+            }
+        }
+    }
+
+    pub fn on_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.state = CircuitState::Closed;
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+        inner.opened_at_utc = None;
+        inner.probe_started = None;
+    }
+
+    // the call tells nothing about the health of the provider (e.g. an unsupported base),
+    // the state is kept and a pending probe is released for the next call
+    pub fn on_neutral(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state == CircuitState::HalfOpen {
+            inner.probe_started = None;
+        }
+    }
+
+    pub fn on_failure(&self) {
+        self.on_failure_at(Instant::now());
+    }
+
+    fn on_failure_at(&self, now: Instant) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures += 1;
+        let trip = match inner.state {
+            CircuitState::HalfOpen => true,
+            CircuitState::Closed => inner.consecutive_failures >= self.failure_threshold,
+            CircuitState::Open => false,
+        };
+        if trip {
+            inner.state = CircuitState::Open;
+            inner.opened_at = Some(now);
+            inner.opened_at_utc = Some(OffsetDateTime::now_utc());
+            inner.probe_started = None;
+        }
+    }
+
+    pub fn status(&self) -> CircuitStatus {
+        let inner = self.inner.lock().unwrap();
+        CircuitStatus {
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            opened_at: inner.opened_at_utc,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new(3, COOLDOWN);
+        let now = Instant::now();
+
+        breaker.on_failure_at(now);
+        breaker.on_failure_at(now);
+        assert_eq!(breaker.status().state, CircuitState::Closed);
+        assert!(breaker.try_acquire_at(now));
+
+        breaker.on_failure_at(now);
+        let status = breaker.status();
+        assert_eq!(status.state, CircuitState::Open);
+        assert_eq!(status.consecutive_failures, 3);
+        assert!(status.opened_at.is_some());
+        assert!(!breaker.try_acquire_at(now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_success_resets_failure_count() {
+        let breaker = CircuitBreaker::new(2, COOLDOWN);
+        let now = Instant::now();
+
+        breaker.on_failure_at(now);
+        breaker.on_success();
+        breaker.on_failure_at(now);
+
+        assert_eq!(breaker.status().state, CircuitState::Closed);
+        assert_eq!(breaker.status().consecutive_failures, 1);
+    }
+
+    #[test]
+    fn test_half_open_probe_after_cooldown() {
+        let breaker = CircuitBreaker::new(1, COOLDOWN);
+        let now = Instant::now();
+        breaker.on_failure_at(now);
+
+        let later = now + COOLDOWN;
+        assert!(breaker.try_acquire_at(later));
+        assert_eq!(breaker.status().state, CircuitState::HalfOpen);
+        // only a single probe is let through
+        assert!(!breaker.try_acquire_at(later));
+
+        breaker.on_success();
+        assert_eq!(breaker.status().state, CircuitState::Closed);
+        assert!(breaker.try_acquire_at(later));
+    }
+
+    #[test]
+    fn test_neutral_probe_keeps_half_open() {
+        let breaker = CircuitBreaker::new(1, COOLDOWN);
+        let now = Instant::now();
+        breaker.on_failure_at(now);
+        let later = now + COOLDOWN;
+        assert!(breaker.try_acquire_at(later));
+
+        breaker.on_neutral();
+
+        assert_eq!(breaker.status().state, CircuitState::HalfOpen);
+        assert_eq!(breaker.status().consecutive_failures, 1);
+        assert!(breaker.try_acquire_at(later));
+    }
+
+    #[test]
+    fn test_failed_probe_opens_again() {
+        let breaker = CircuitBreaker::new(3, COOLDOWN);
+        let now = Instant::now();
+        for _ in 0..3 {
+            breaker.on_failure_at(now);
+        }
+
+        let later = now + COOLDOWN;
+        assert!(breaker.try_acquire_at(later));
+        breaker.on_failure_at(later);
+
+        assert_eq!(breaker.status().state, CircuitState::Open);
+        assert!(!breaker.try_acquire_at(later + Duration::from_secs(1)));
+        assert!(breaker.try_acquire_at(later + COOLDOWN));
+    }
+}
@@ -0,0 +1,177 @@
+use crate::route::model::{Conversion, Rounding};
+use crate::service::currency::currency_info;
+use crate::service::legacy::Legacy;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+// none when the converted amount doesn't fit in a decimal
+pub fn convert(
+    base: &str,
+    counter: &str,
+    amount: Decimal,
+    rate: Decimal,
+    rounding: Rounding,
+) -> Option<Conversion> {
+    let raw = amount.checked_mul(rate)?;
+    Some(Conversion {
+        base: base.to_string(),
+        counter: counter.to_string(),
+        amount,
+        rate,
+        raw,
+        rounded: round(raw, counter, rounding)?,
+        rounding,
+        format: None,
+        overridden: false,
+    })
+}
+
+// the euro rules convert an amount of a legacy currency by dividing it by the conversion factor,
+// then applying the rate of the successor, the rate of the pair is reported for information
+pub fn convert_legacy(
+    legacy: &Legacy,
+    counter: &str,
+    amount: Decimal,
+    successor_rate: Decimal,
+    rounding: Rounding,
+) -> Option<Conversion> {
+    let raw = amount
+        .checked_div(legacy.factor)?
+        .checked_mul(successor_rate)?;
+    Some(Conversion {
+        base: legacy.code.to_string(),
+        counter: counter.to_string(),
+        amount,
+        rate: successor_rate.checked_div(legacy.factor)?,
+        raw,
+        rounded: round(raw, counter, rounding)?,
+        rounding,
+        format: None,
+        overridden: false,
+    })
+}
+
+// rounds to the minor units of the currency from the catalogue, amounts of currencies without minor units
+// (unknown to the catalogue or gold) are kept as they are, none when the count of coins overflows
+pub fn round(amount: Decimal, code: &str, rounding: Rounding) -> Option<Decimal> {
+    let Some(info) = currency_info(code) else {
+        return Some(amount);
+    };
+    let Some(minor_units) = info.minor_units else {
+        return Some(amount);
+    };
+    match (rounding, info.cash_rounding) {
+        (Rounding::HalfEven, _) => {
+            Some(amount.round_dp_with_strategy(minor_units, RoundingStrategy::MidpointNearestEven))
+        }
+        (Rounding::HalfUp, _) | (Rounding::Cash, None) => {
+            Some(amount.round_dp_with_strategy(minor_units, RoundingStrategy::MidpointAwayFromZero))
+        }
+        (Rounding::Cash, Some(increment)) => {
+            let coin = Decimal::new(increment.into(), minor_units);
+            amount
+                .checked_div(coin)?
+                .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+                .checked_mul(coin)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::legacy::find_legacy;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_round_half_even() {
+        assert_eq!(
+            round(dec!(1.125), "EUR", Rounding::HalfEven),
+            Some(dec!(1.12))
+        );
+        assert_eq!(
+            round(dec!(1.135), "EUR", Rounding::HalfEven),
+            Some(dec!(1.14))
+        );
+        assert_eq!(
+            round(dec!(4603.5), "UGX", Rounding::HalfEven),
+            Some(dec!(4604))
+        );
+        assert_eq!(
+            round(dec!(0.1235), "KWD", Rounding::HalfEven),
+            Some(dec!(0.124))
+        );
+    }
+
+    #[test]
+    fn test_round_half_up() {
+        assert_eq!(
+            round(dec!(1.125), "EUR", Rounding::HalfUp),
+            Some(dec!(1.13))
+        );
+        assert_eq!(
+            round(dec!(-1.125), "EUR", Rounding::HalfUp),
+            Some(dec!(-1.13))
+        );
+        assert_eq!(
+            round(dec!(12344.5), "UGX", Rounding::HalfUp),
+            Some(dec!(12345))
+        );
+    }
+
+    #[test]
+    fn test_round_cash() {
+        assert_eq!(round(dec!(1.024), "CHF", Rounding::Cash), Some(dec!(1.00)));
+        assert_eq!(round(dec!(1.025), "CHF", Rounding::Cash), Some(dec!(1.05)));
+        assert_eq!(round(dec!(1.074), "CHF", Rounding::Cash), Some(dec!(1.05)));
+        assert_eq!(round(dec!(12.26), "DKK", Rounding::Cash), Some(dec!(12.50)));
+        assert_eq!(round(dec!(99.49), "SEK", Rounding::Cash), Some(dec!(99)));
+        // no coins to round to, same as half up
+        assert_eq!(round(dec!(1.125), "EUR", Rounding::Cash), Some(dec!(1.13)));
+    }
+
+    #[test]
+    fn test_round_without_minor_units() {
+        assert_eq!(
+            round(dec!(0.0012345), "XAU", Rounding::HalfEven),
+            Some(dec!(0.0012345))
+        );
+        assert_eq!(
+            round(dec!(0.0012345), "BTC", Rounding::HalfUp),
+            Some(dec!(0.0012345))
+        );
+    }
+
+    #[test]
+    fn test_convert() {
+        let conversion = convert("EUR", "CHF", dec!(1299), dec!(0.9415), Rounding::Cash).unwrap();
+
+        assert_eq!(conversion.raw, dec!(1223.0085));
+        assert_eq!(conversion.rounded, dec!(1223.00));
+        assert_eq!(conversion.rounding, Rounding::Cash);
+    }
+
+    #[test]
+    fn test_convert_legacy() {
+        let dem = find_legacy("DEM").unwrap();
+
+        let conversion =
+            convert_legacy(dem, "EUR", dec!(1000), Decimal::ONE, Rounding::HalfEven).unwrap();
+        assert_eq!(conversion.base, "DEM");
+        assert_eq!(conversion.rounded, dec!(511.29));
+        // DEM to FRF through the euro, 1000 / 1.95583 * 6.55957
+        let conversion =
+            convert_legacy(dem, "FRF", dec!(1000), dec!(6.55957), Rounding::HalfEven).unwrap();
+        assert_eq!(conversion.rounded, dec!(3353.85));
+    }
+
+    #[test]
+    fn test_convert_overflow() {
+        assert!(convert("EUR", "UGX", Decimal::MAX, dec!(4050.2), Rounding::HalfEven).is_none());
+        // the amount fits, the count of 0.05 coins doesn't
+        assert!(convert("EUR", "CHF", Decimal::MAX, dec!(1), Rounding::Cash).is_none());
+        assert_eq!(
+            round(Decimal::MAX, "CHF", Rounding::HalfUp),
+            Some(Decimal::MAX)
+        );
+    }
+}
@@ -0,0 +1,367 @@
+use crate::route::model::Currency;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+// built-in ISO 4217 catalogue, the providers deliver the codes and names only
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurrencyInfo {
+    pub code: &'static str,
+    // three digits, with leading zeros
+    pub numeric: &'static str,
+    // digits after the decimal separator, none for the precious metals and the special drawing rights
+    pub minor_units: Option<u32>,
+    pub symbol: Option<&'static str>,
+    pub name: &'static str,
+    // ISO 3166 alpha-2 codes of the countries using the currency
+    pub countries: &'static [&'static str],
+    // false for the withdrawn currencies, e.g. the ones replaced by the euro
+    pub active: bool,
+    // smallest coin in minor units when cash amounts are rounded further, 5 for CHF 0.05
+    pub cash_rounding: Option<u32>,
+}
+
+const fn iso(
+    code: &'static str,
+    numeric: &'static str,
+    minor_units: u32,
+    symbol: &'static str,
+    name: &'static str,
+    countries: &'static [&'static str],
+) -> CurrencyInfo {
+    CurrencyInfo {
+        code,
+        numeric,
+        minor_units: Some(minor_units),
+        symbol: Some(symbol),
+        name,
+        countries,
+        active: true,
+        cash_rounding: None,
+    }
+}
+
+// precious metals and funds, traded in units without a symbol
+const fn unit(code: &'static str, numeric: &'static str, name: &'static str) -> CurrencyInfo {
+    CurrencyInfo {
+        code,
+        numeric,
+        minor_units: None,
+        symbol: None,
+        name,
+        countries: &[],
+        active: true,
+        cash_rounding: None,
+    }
+}
+
+impl CurrencyInfo {
+    const fn historic(self) -> CurrencyInfo {
+        CurrencyInfo {
+            active: false,
+            ..self
+        }
+    }
+
+    const fn cash(self, increment: u32) -> CurrencyInfo {
+        CurrencyInfo {
+            cash_rounding: Some(increment),
+            ..self
+        }
+    }
+}
+
+#[rustfmt::skip]
+static CATALOGUE: &[CurrencyInfo] = &[
+    iso("AED", "784", 2, "د.إ", "UAE Dirham", &["AE"]),
+    iso("AFN", "971", 2, "؋", "Afghani", &["AF"]),
+    iso("ALL", "008", 2, "L", "Lek", &["AL"]),
+    iso("AMD", "051", 2, "֏", "Armenian Dram", &["AM"]),
+    iso("ANG", "532", 2, "ƒ", "Netherlands Antillean Guilder", &["CW", "SX"]),
+    iso("AOA", "973", 2, "Kz", "Kwanza", &["AO"]),
+    iso("ARS", "032", 2, "$", "Argentine Peso", &["AR"]),
+    iso("AUD", "036", 2, "A$", "Australian Dollar", &["AU", "CC", "CX", "HM", "KI", "NF", "NR", "TV"]),
+    iso("AWG", "533", 2, "ƒ", "Aruban Florin", &["AW"]),
+    iso("AZN", "944", 2, "₼", "Azerbaijan Manat", &["AZ"]),
+    iso("BAM", "977", 2, "KM", "Convertible Mark", &["BA"]),
+    iso("BBD", "052", 2, "$", "Barbados Dollar", &["BB"]),
+    iso("BDT", "050", 2, "৳", "Taka", &["BD"]),
+    iso("BHD", "048", 3, ".د.ب", "Bahraini Dinar", &["BH"]),
+    iso("BIF", "108", 0, "FBu", "Burundi Franc", &["BI"]),
+    iso("BMD", "060", 2, "$", "Bermudian Dollar", &["BM"]),
+    iso("BND", "096", 2, "$", "Brunei Dollar", &["BN"]),
+    iso("BOB", "068", 2, "Bs", "Boliviano", &["BO"]),
+    iso("BRL", "986", 2, "R$", "Brazilian Real", &["BR"]),
+    iso("BSD", "044", 2, "$", "Bahamian Dollar", &["BS"]),
+    iso("BTN", "064", 2, "Nu.", "Ngultrum", &["BT"]),
+    iso("BWP", "072", 2, "P", "Pula", &["BW"]),
+    iso("BYN", "933", 2, "Br", "Belarusian Ruble", &["BY"]),
+    iso("BZD", "084", 2, "$", "Belize Dollar", &["BZ"]),
+    iso("CAD", "124", 2, "CA$", "Canadian Dollar", &["CA"]).cash(5),
+    iso("CDF", "976", 2, "FC", "Congolese Franc", &["CD"]),
+    iso("CHF", "756", 2, "CHF", "Swiss Franc", &["CH", "LI"]).cash(5),
+    iso("CLP", "152", 0, "$", "Chilean Peso", &["CL"]),
+    iso("CNY", "156", 2, "¥", "Yuan Renminbi", &["CN"]),
+    iso("COP", "170", 2, "$", "Colombian Peso", &["CO"]),
+    iso("CRC", "188", 2, "₡", "Costa Rican Colon", &["CR"]),
+    iso("CUP", "192", 2, "$", "Cuban Peso", &["CU"]),
+    iso("CVE", "132", 2, "$", "Cabo Verde Escudo", &["CV"]),
+    iso("CZK", "203", 2, "Kč", "Czech Koruna", &["CZ"]).cash(100),
+    iso("DJF", "262", 0, "Fdj", "Djibouti Franc", &["DJ"]),
+    iso("DKK", "208", 2, "kr", "Danish Krone", &["DK", "FO", "GL"]).cash(50),
+    iso("DOP", "214", 2, "$", "Dominican Peso", &["DO"]),
+    iso("DZD", "012", 2, "د.ج", "Algerian Dinar", &["DZ"]),
+    iso("EGP", "818", 2, "E£", "Egyptian Pound", &["EG"]),
+    iso("ERN", "232", 2, "Nfk", "Nakfa", &["ER"]),
+    iso("ETB", "230", 2, "Br", "Ethiopian Birr", &["ET"]),
+    iso("EUR", "978", 2, "€", "Euro", &[
+        "AD", "AT", "BE", "BG", "CY", "DE", "EE", "ES", "FI", "FR", "GR", "HR", "IE", "IT", "LT",
+        "LU", "LV", "MC", "ME", "MT", "NL", "PT", "SI", "SK", "SM", "VA", "XK",
+    ]),
+    iso("FJD", "242", 2, "$", "Fiji Dollar", &["FJ"]),
+    iso("FKP", "238", 2, "£", "Falkland Islands Pound", &["FK"]),
+    iso("GBP", "826", 2, "£", "Pound Sterling", &["GB", "GG", "IM", "JE"]),
+    iso("GEL", "981", 2, "₾", "Lari", &["GE"]),
+    iso("GHS", "936", 2, "GH₵", "Ghana Cedi", &["GH"]),
+    iso("GIP", "292", 2, "£", "Gibraltar Pound", &["GI"]),
+    iso("GMD", "270", 2, "D", "Dalasi", &["GM"]),
+    iso("GNF", "324", 0, "FG", "Guinean Franc", &["GN"]),
+    iso("GTQ", "320", 2, "Q", "Quetzal", &["GT"]),
+    iso("GYD", "328", 2, "$", "Guyana Dollar", &["GY"]),
+    iso("HKD", "344", 2, "HK$", "Hong Kong Dollar", &["HK"]),
+    iso("HNL", "340", 2, "L", "Lempira", &["HN"]),
+    iso("HTG", "332", 2, "G", "Gourde", &["HT"]),
+    iso("HUF", "348", 2, "Ft", "Forint", &["HU"]).cash(100),
+    iso("IDR", "360", 2, "Rp", "Rupiah", &["ID"]),
+    iso("ILS", "376", 2, "₪", "New Israeli Sheqel", &["IL"]),
+    iso("INR", "356", 2, "₹", "Indian Rupee", &["BT", "IN"]),
+    iso("IQD", "368", 3, "ع.د", "Iraqi Dinar", &["IQ"]),
+    iso("IRR", "364", 2, "﷼", "Iranian Rial", &["IR"]),
+    iso("ISK", "352", 0, "kr", "Iceland Krona", &["IS"]),
+    iso("JMD", "388", 2, "$", "Jamaican Dollar", &["JM"]),
+    iso("JOD", "400", 3, "د.ا", "Jordanian Dinar", &["JO"]),
+    iso("JPY", "392", 0, "¥", "Yen", &["JP"]),
+    iso("KES", "404", 2, "KSh", "Kenyan Shilling", &["KE"]),
+    iso("KGS", "417", 2, "с", "Som", &["KG"]),
+    iso("KHR", "116", 2, "៛", "Riel", &["KH"]),
+    iso("KMF", "174", 0, "CF", "Comorian Franc", &["KM"]),
+    iso("KPW", "408", 2, "₩", "North Korean Won", &["KP"]),
+    iso("KRW", "410", 0, "₩", "Won", &["KR"]),
+    iso("KWD", "414", 3, "د.ك", "Kuwaiti Dinar", &["KW"]),
+    iso("KYD", "136", 2, "$", "Cayman Islands Dollar", &["KY"]),
+    iso("KZT", "398", 2, "₸", "Tenge", &["KZ"]),
+    iso("LAK", "418", 2, "₭", "Lao Kip", &["LA"]),
+    iso("LBP", "422", 2, "ل.ل", "Lebanese Pound", &["LB"]),
+    iso("LKR", "144", 2, "Rs", "Sri Lanka Rupee", &["LK"]),
+    iso("LRD", "430", 2, "$", "Liberian Dollar", &["LR"]),
+    iso("LSL", "426", 2, "L", "Loti", &["LS"]),
+    iso("LYD", "434", 3, "ل.د", "Libyan Dinar", &["LY"]),
+    iso("MAD", "504", 2, "د.م.", "Moroccan Dirham", &["EH", "MA"]),
+    iso("MDL", "498", 2, "L", "Moldovan Leu", &["MD"]),
+    iso("MGA", "969", 2, "Ar", "Malagasy Ariary", &["MG"]),
+    iso("MKD", "807", 2, "ден", "Denar", &["MK"]),
+    iso("MMK", "104", 2, "K", "Kyat", &["MM"]),
+    iso("MNT", "496", 2, "₮", "Tugrik", &["MN"]),
+    iso("MOP", "446", 2, "MOP$", "Pataca", &["MO"]),
+    iso("MRU", "929", 2, "UM", "Ouguiya", &["MR"]),
+    iso("MUR", "480", 2, "₨", "Mauritius Rupee", &["MU"]),
+    iso("MVR", "462", 2, "Rf", "Rufiyaa", &["MV"]),
+    iso("MWK", "454", 2, "MK", "Malawi Kwacha", &["MW"]),
+    iso("MXN", "484", 2, "$", "Mexican Peso", &["MX"]),
+    iso("MYR", "458", 2, "RM", "Malaysian Ringgit", &["MY"]),
+    iso("MZN", "943", 2, "MT", "Mozambique Metical", &["MZ"]),
+    iso("NAD", "516", 2, "$", "Namibia Dollar", &["NA"]),
+    iso("NGN", "566", 2, "₦", "Naira", &["NG"]),
+    iso("NIO", "558", 2, "C$", "Cordoba Oro", &["NI"]),
+    iso("NOK", "578", 2, "kr", "Norwegian Krone", &["BV", "NO", "SJ"]).cash(100),
+    iso("NPR", "524", 2, "Rs", "Nepalese Rupee", &["NP"]),
+    iso("NZD", "554", 2, "NZ$", "New Zealand Dollar", &["CK", "NU", "NZ", "PN", "TK"]),
+    iso("OMR", "512", 3, "ر.ع.", "Rial Omani", &["OM"]),
+    iso("PAB", "590", 2, "B/.", "Balboa", &["PA"]),
+    iso("PEN", "604", 2, "S/", "Sol", &["PE"]),
+    iso("PGK", "598", 2, "K", "Kina", &["PG"]),
+    iso("PHP", "608", 2, "₱", "Philippine Peso", &["PH"]),
+    iso("PKR", "586", 2, "Rs", "Pakistan Rupee", &["PK"]),
+    iso("PLN", "985", 2, "zł", "Zloty", &["PL"]),
+    iso("PYG", "600", 0, "₲", "Guarani", &["PY"]),
+    iso("QAR", "634", 2, "ر.ق", "Qatari Rial", &["QA"]),
+    iso("RON", "946", 2, "lei", "Romanian Leu", &["RO"]),
+    iso("RSD", "941", 2, "дин.", "Serbian Dinar", &["RS"]),
+    iso("RUB", "643", 2, "₽", "Russian Ruble", &["RU"]),
+    iso("RWF", "646", 0, "FRw", "Rwanda Franc", &["RW"]),
+    iso("SAR", "682", 2, "ر.س", "Saudi Riyal", &["SA"]),
+    iso("SBD", "090", 2, "$", "Solomon Islands Dollar", &["SB"]),
+    iso("SCR", "690", 2, "₨", "Seychelles Rupee", &["SC"]),
+    iso("SDG", "938", 2, "ج.س.", "Sudanese Pound", &["SD"]),
+    iso("SEK", "752", 2, "kr", "Swedish Krona", &["SE"]).cash(100),
+    iso("SGD", "702", 2, "S$", "Singapore Dollar", &["SG"]),
+    iso("SHP", "654", 2, "£", "Saint Helena Pound", &["SH"]),
+    iso("SLE", "925", 2, "Le", "Leone", &["SL"]),
+    iso("SOS", "706", 2, "Sh", "Somali Shilling", &["SO"]),
+    iso("SRD", "968", 2, "$", "Surinam Dollar", &["SR"]),
+    iso("SSP", "728", 2, "£", "South Sudanese Pound", &["SS"]),
+    iso("STN", "930", 2, "Db", "Dobra", &["ST"]),
+    iso("SVC", "222", 2, "₡", "El Salvador Colon", &["SV"]),
+    iso("SYP", "760", 2, "£S", "Syrian Pound", &["SY"]),
+    iso("SZL", "748", 2, "L", "Lilangeni", &["SZ"]),
+    iso("THB", "764", 2, "฿", "Baht", &["TH"]),
+    iso("TJS", "972", 2, "SM", "Somoni", &["TJ"]),
+    iso("TMT", "934", 2, "m", "Turkmenistan New Manat", &["TM"]),
+    iso("TND", "788", 3, "د.ت", "Tunisian Dinar", &["TN"]),
+    iso("TOP", "776", 2, "T$", "Pa’anga", &["TO"]),
+    iso("TRY", "949", 2, "₺", "Turkish Lira", &["TR"]),
+    iso("TTD", "780", 2, "$", "Trinidad and Tobago Dollar", &["TT"]),
+    iso("TWD", "901", 2, "NT$", "New Taiwan Dollar", &["TW"]),
+    iso("TZS", "834", 2, "TSh", "Tanzanian Shilling", &["TZ"]),
+    iso("UAH", "980", 2, "₴", "Hryvnia", &["UA"]),
+    iso("UGX", "800", 0, "USh", "Uganda Shilling", &["UG"]),
+    iso("USD", "840", 2, "$", "US Dollar", &[
+        "AS", "BQ", "EC", "FM", "GU", "IO", "MH", "MP", "PR", "PW", "SV", "TC", "TL", "UM", "US",
+        "VG", "VI",
+    ]),
+    iso("UYU", "858", 2, "$", "Peso Uruguayo", &["UY"]),
+    iso("UZS", "860", 2, "soʻm", "Uzbekistan Sum", &["UZ"]),
+    iso("VES", "928", 2, "Bs.S", "Bolívar Soberano", &["VE"]),
+    iso("VND", "704", 0, "₫", "Dong", &["VN"]),
+    iso("VUV", "548", 0, "VT", "Vatu", &["VU"]),
+    iso("WST", "882", 2, "T", "Tala", &["WS"]),
+    iso("XAF", "950", 0, "FCFA", "CFA Franc BEAC", &["CF", "CG", "CM", "GA", "GQ", "TD"]),
+    iso("XCD", "951", 2, "EC$", "East Caribbean Dollar", &["AG", "AI", "DM", "GD", "KN", "LC", "MS", "VC"]),
+    iso("XOF", "952", 0, "CFA", "CFA Franc BCEAO", &["BF", "BJ", "CI", "GW", "ML", "NE", "SN", "TG"]),
+    iso("XPF", "953", 0, "₣", "CFP Franc", &["NC", "PF", "WF"]),
+    iso("YER", "886", 2, "﷼", "Yemeni Rial", &["YE"]),
+    iso("ZAR", "710", 2, "R", "Rand", &["LS", "NA", "ZA"]),
+    iso("ZMW", "967", 2, "ZK", "Zambian Kwacha", &["ZM"]),
+    iso("ZWG", "924", 2, "ZiG", "Zimbabwe Gold", &["ZW"]),
+    unit("XAG", "961", "Silver"),
+    unit("XAU", "959", "Gold"),
+    unit("XDR", "960", "SDR (Special Drawing Right)"),
+    unit("XPD", "964", "Palladium"),
+    unit("XPT", "962", "Platinum"),
+    // withdrawn, still found in the historical rates
+    iso("ATS", "040", 2, "S", "Schilling", &["AT"]).historic(),
+    iso("BEF", "056", 0, "fr.", "Belgian Franc", &["BE"]).historic(),
+    iso("BGN", "975", 2, "лв", "Bulgarian Lev", &["BG"]).historic(),
+    iso("BYR", "974", 0, "Br", "Belarusian Ruble", &["BY"]).historic(),
+    iso("CUC", "931", 2, "CUC$", "Peso Convertible", &["CU"]).historic(),
+    iso("CYP", "196", 2, "£", "Cyprus Pound", &["CY"]).historic(),
+    iso("DEM", "276", 2, "DM", "Deutsche Mark", &["DE"]).historic(),
+    iso("EEK", "233", 2, "kr", "Kroon", &["EE"]).historic(),
+    iso("ESP", "724", 0, "Pts", "Spanish Peseta", &["ES"]).historic(),
+    iso("FIM", "246", 2, "mk", "Markka", &["FI"]).historic(),
+    iso("FRF", "250", 2, "F", "French Franc", &["FR"]).historic(),
+    iso("GHC", "288", 2, "₵", "Cedi", &["GH"]).historic(),
+    iso("GRD", "300", 0, "₯", "Drachma", &["GR"]).historic(),
+    iso("HRK", "191", 2, "kn", "Kuna", &["HR"]).historic(),
+    iso("IEP", "372", 2, "£", "Irish Pound", &["IE"]).historic(),
+    iso("ITL", "380", 0, "₤", "Italian Lira", &["IT"]).historic(),
+    iso("LTL", "440", 2, "Lt", "Lithuanian Litas", &["LT"]).historic(),
+    iso("LUF", "442", 0, "F", "Luxembourg Franc", &["LU"]).historic(),
+    iso("LVL", "428", 2, "Ls", "Latvian Lats", &["LV"]).historic(),
+    iso("MRO", "478", 2, "UM", "Ouguiya", &["MR"]).historic(),
+    iso("MTL", "470", 2, "Lm", "Maltese Lira", &["MT"]).historic(),
+    iso("MZM", "508", 2, "MT", "Mozambique Metical", &["MZ"]).historic(),
+    iso("NLG", "528", 2, "ƒ", "Netherlands Guilder", &["NL"]).historic(),
+    iso("PTE", "620", 0, "$", "Portuguese Escudo", &["PT"]).historic(),
+    iso("SIT", "705", 2, "SIT", "Tolar", &["SI"]).historic(),
+    iso("SKK", "703", 2, "Sk", "Slovak Koruna", &["SK"]).historic(),
+    iso("SLL", "694", 2, "Le", "Leone", &["SL"]).historic(),
+    iso("STD", "678", 2, "Db", "Dobra", &["ST"]).historic(),
+    iso("VEF", "937", 2, "Bs.F", "Bolívar", &["VE"]).historic(),
+    iso("ZMK", "894", 2, "ZK", "Zambian Kwacha", &["ZM"]).historic(),
+    iso("ZWL", "932", 2, "Z$", "Zimbabwe Dollar", &["ZW"]).historic(),
+];
+
+static BY_CODE: LazyLock<HashMap<&'static str, &'static CurrencyInfo>> =
+    LazyLock::new(|| CATALOGUE.iter().map(|info| (info.code, info)).collect());
+
+// case insensitive lookup of the ISO 4217 code
+pub fn currency_info(code: &str) -> Option<&'static CurrencyInfo> {
+    BY_CODE.get(code.to_uppercase().as_str()).copied()
+}
+
+impl From<&CurrencyInfo> for Currency {
+    fn from(info: &CurrencyInfo) -> Currency {
+        Currency {
+            code: info.code.to_string(),
+            name: info.name.to_string(),
+            numeric: Some(info.numeric.to_string()),
+            minor_units: info.minor_units,
+            symbol: info.symbol.map(str::to_string),
+            countries: info.countries.iter().map(|c| c.to_string()).collect(),
+            active: info.active,
+            cash_rounding: info.cash_rounding,
+        }
+    }
+}
+
+// metadata of a currency supported by the providers, the ones missing in the catalogue
+// (e.g. crypto currencies) keep the name delivered by the providers only
+pub fn describe(code: &str, provider_name: &str) -> Currency {
+    match currency_info(code) {
+        Some(info) => Currency::from(info),
+        None => Currency {
+            code: code.to_uppercase(),
+            name: provider_name.to_string(),
+            numeric: None,
+            minor_units: None,
+            symbol: None,
+            countries: Vec::new(),
+            active: true,
+            cash_rounding: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_catalogue_is_consistent() {
+        let codes = CATALOGUE.iter().map(|c| c.code).collect::<HashSet<_>>();
+        assert_eq!(codes.len(), CATALOGUE.len(), "duplicated code");
+        let active_numerics = CATALOGUE
+            .iter()
+            .filter(|c| c.active)
+            .map(|c| c.numeric)
+            .collect::<HashSet<_>>();
+        assert_eq!(
+            active_numerics.len(),
+            CATALOGUE.iter().filter(|c| c.active).count(),
+            "duplicated numeric code"
+        );
+        for info in CATALOGUE {
+            assert!(info.code.len() == 3 && info.code.chars().all(|c| c.is_ascii_uppercase()));
+            assert!(info.numeric.len() == 3 && info.numeric.chars().all(|c| c.is_ascii_digit()));
+            assert!(info.countries.iter().all(|c| c.len() == 2));
+        }
+    }
+
+    #[test]
+    fn test_currency_info() {
+        let ugx = currency_info("ugx").unwrap();
+        assert_eq!(ugx.numeric, "800");
+        assert_eq!(ugx.minor_units, Some(0));
+        assert_eq!(currency_info("KHR").unwrap().symbol, Some("៛"));
+        assert_eq!(currency_info("KWD").unwrap().minor_units, Some(3));
+        assert_eq!(currency_info("XAU").unwrap().minor_units, None);
+        assert!(!currency_info("DEM").unwrap().active);
+        assert_eq!(currency_info("CHF").unwrap().cash_rounding, Some(5));
+        assert!(currency_info("XYZ").is_none());
+    }
+
+    #[test]
+    fn test_describe() {
+        let chf = describe("CHF", "Swiss franc");
+        assert_eq!(chf.name, "Swiss Franc");
+        assert_eq!(chf.numeric.as_deref(), Some("756"));
+        assert_eq!(chf.countries, vec!["CH", "LI"]);
+
+        let btc = describe("btc", "Bitcoin");
+        assert_eq!(btc.code, "BTC");
+        assert_eq!(btc.name, "Bitcoin");
+        assert_eq!(btc.numeric, None);
+        assert!(btc.active);
+    }
+}
@@ -0,0 +1,119 @@
+// display names of the currencies from CLDR, the providers deliver the English names only
+pub const LANGUAGES: &[&str] = &["de", "fr", "hu"];
+
+#[rustfmt::skip]
+static NAMES: &[(&str, [&str; 3])] = &[
+    // code, [de, fr, hu]
+    ("AED", ["VAE-Dirham", "dirham des Émirats arabes unis", "EAE-dirham"]),
+    ("ARS", ["Argentinischer Peso", "peso argentin", "argentin peso"]),
+    ("AUD", ["Australischer Dollar", "dollar australien", "ausztrál dollár"]),
+    ("BDT", ["Bangladesch-Taka", "taka bangladeshi", "bangladesi taka"]),
+    ("BGN", ["Bulgarischer Lew", "lev bulgare", "bolgár új leva"]),
+    ("BRL", ["Brasilianischer Real", "réal brésilien", "brazil real"]),
+    ("CAD", ["Kanadischer Dollar", "dollar canadien", "kanadai dollár"]),
+    ("CHF", ["Schweizer Franken", "franc suisse", "svájci frank"]),
+    ("CLP", ["Chilenischer Peso", "peso chilien", "chilei peso"]),
+    ("CNY", ["Renminbi Yuan", "yuan renminbi chinois", "kínai jüan"]),
+    ("COP", ["Kolumbianischer Peso", "peso colombien", "kolumbiai peso"]),
+    ("CZK", ["Tschechische Krone", "couronne tchèque", "cseh korona"]),
+    ("DEM", ["Deutsche Mark", "mark allemand", "német márka"]),
+    ("DKK", ["Dänische Krone", "couronne danoise", "dán korona"]),
+    ("EGP", ["Ägyptisches Pfund", "livre égyptienne", "egyiptomi font"]),
+    ("ETB", ["Äthiopischer Birr", "birr éthiopien", "etiópiai birr"]),
+    ("EUR", ["Euro", "euro", "euró"]),
+    ("FRF", ["Französischer Franc", "franc français", "francia frank"]),
+    ("GBP", ["Britisches Pfund", "livre sterling", "angol font"]),
+    ("GHS", ["Ghanaischer Cedi", "cédi ghanéen", "ghánai cedi"]),
+    ("HKD", ["Hongkong-Dollar", "dollar de Hong Kong", "hongkongi dollár"]),
+    ("HRK", ["Kroatischer Kuna", "kuna croate", "horvát kuna"]),
+    ("HUF", ["Ungarischer Forint", "forint hongrois", "magyar forint"]),
+    ("IDR", ["Indonesische Rupiah", "roupie indonésienne", "indonéz rúpia"]),
+    ("ILS", ["Israelischer Neuer Schekel", "nouveau shekel israélien", "izraeli új sékel"]),
+    ("INR", ["Indische Rupie", "roupie indienne", "indiai rúpia"]),
+    ("ISK", ["Isländische Krone", "couronne islandaise", "izlandi korona"]),
+    ("JPY", ["Japanischer Yen", "yen japonais", "japán jen"]),
+    ("KES", ["Kenia-Schilling", "shilling kényan", "kenyai shilling"]),
+    ("KHR", ["Kambodschanischer Riel", "riel cambodgien", "kambodzsai riel"]),
+    ("KRW", ["Südkoreanischer Won", "won sud-coréen", "dél-koreai won"]),
+    ("LAK", ["Laotischer Kip", "kip loatien", "laoszi kip"]),
+    ("MAD", ["Marokkanischer Dirham", "dirham marocain", "marokkói dirham"]),
+    ("MWK", ["Malawi-Kwacha", "kwacha malawite", "malawi kwacha"]),
+    ("MXN", ["Mexikanischer Peso", "peso mexicain", "mexikói peso"]),
+    ("MYR", ["Malaysischer Ringgit", "ringgit malais", "maláj ringgit"]),
+    ("MZN", ["Mosambikanischer Metical", "metical mozambicain", "mozambiki metikális"]),
+    ("NGN", ["Nigerianischer Naira", "naira nigérian", "nigériai naira"]),
+    ("NOK", ["Norwegische Krone", "couronne norvégienne", "norvég korona"]),
+    ("NPR", ["Nepalesische Rupie", "roupie népalaise", "nepáli rúpia"]),
+    ("NZD", ["Neuseeland-Dollar", "dollar néo-zélandais", "új-zélandi dollár"]),
+    ("PEN", ["Peruanischer Sol", "sol péruvien", "perui sol"]),
+    ("PHP", ["Philippinischer Peso", "peso philippin", "Fülöp-szigeteki peso"]),
+    ("PKR", ["Pakistanische Rupie", "roupie pakistanaise", "pakisztáni rúpia"]),
+    ("PLN", ["Polnischer Złoty", "zloty polonais", "lengyel zloty"]),
+    ("QAR", ["Katar-Riyal", "riyal qatari", "katari riyal"]),
+    ("RON", ["Rumänischer Leu", "leu roumain", "román lej"]),
+    ("RUB", ["Russischer Rubel", "rouble russe", "orosz rubel"]),
+    ("RWF", ["Ruanda-Franc", "franc rwandais", "ruandai frank"]),
+    ("SAR", ["Saudi-Rial", "riyal saoudien", "szaúdi riyal"]),
+    ("SEK", ["Schwedische Krone", "couronne suédoise", "svéd korona"]),
+    ("SGD", ["Singapur-Dollar", "dollar de Singapour", "szingapúri dollár"]),
+    ("THB", ["Thailändischer Baht", "baht thaïlandais", "thai baht"]),
+    ("TRY", ["Türkische Lira", "livre turque", "török líra"]),
+    ("TWD", ["Neuer Taiwan-Dollar", "nouveau dollar taïwanais", "tajvani új dollár"]),
+    ("TZS", ["Tansania-Schilling", "shilling tanzanien", "tanzániai shilling"]),
+    ("UAH", ["Ukrainische Hrywnja", "hryvnia ukrainienne", "ukrán hrivnya"]),
+    ("UGX", ["Uganda-Schilling", "shilling ougandais", "ugandai shilling"]),
+    ("USD", ["US-Dollar", "dollar des États-Unis", "USA-dollár"]),
+    ("VND", ["Vietnamesischer Dong", "dông vietnamien", "vietnámi dong"]),
+    ("XAF", ["CFA-Franc (BEAC)", "franc CFA (BEAC)", "CFA frank BEAC"]),
+    ("XAU", ["Unze Gold", "or", "arany"]),
+    ("XOF", ["CFA-Franc (BCEAO)", "franc CFA (BCEAO)", "CFA frank BCEAO"]),
+    ("ZAR", ["Südafrikanischer Rand", "rand sud-africain", "dél-afrikai rand"]),
+    ("ZMW", ["Sambischer Kwacha", "kwacha zambien", "zambiai kwacha"]),
+];
+
+// primary language subtag of the supported translations, en is served with the provider names
+pub fn supported_language(tag: &str) -> Option<&'static str> {
+    let language = tag.trim().split(['-', '_']).next().unwrap_or_default();
+    LANGUAGES
+        .iter()
+        .chain(["en"].iter())
+        .find(|l| l.eq_ignore_ascii_case(language))
+        .copied()
+}
+
+pub fn localized_name(code: &str, language: &str) -> Option<&'static str> {
+    let index = LANGUAGES.iter().position(|l| *l == language)?;
+    NAMES
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(code))
+        .map(|(_, names)| names[index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_language() {
+        assert_eq!(supported_language("de-CH"), Some("de"));
+        assert_eq!(supported_language("FR"), Some("fr"));
+        assert_eq!(supported_language("hu_HU"), Some("hu"));
+        assert_eq!(supported_language("en-GB"), Some("en"));
+        assert_eq!(supported_language("it"), None);
+    }
+
+    #[test]
+    fn test_localized_name() {
+        assert_eq!(localized_name("CHF", "de"), Some("Schweizer Franken"));
+        assert_eq!(localized_name("ugx", "fr"), Some("shilling ougandais"));
+        assert_eq!(localized_name("HUF", "hu"), Some("magyar forint"));
+        assert_eq!(localized_name("ZMW", "de"), Some("Sambischer Kwacha"));
+        assert_eq!(localized_name("CHF", "en"), None);
+        assert_eq!(localized_name("BTC", "de"), None);
+    }
+
+    #[test]
+    fn test_names_are_sorted_and_unique() {
+        assert!(NAMES.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+}
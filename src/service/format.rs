@@ -0,0 +1,236 @@
+use crate::route::model::FormattedAmount;
+use crate::service::currency::currency_info;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+const NBSP: char = '\u{a0}';
+const NARROW_NBSP: char = '\u{202f}';
+
+// number and currency conventions of a locale, taken from the CLDR currency patterns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    pub tag: &'static str,
+    decimal: char,
+    group: char,
+    // digits of the groups before the last one, 2 for the Indian lakh and crore
+    secondary_group: usize,
+    // ¤#,##0.00 when true, #,##0.00 ¤ otherwise
+    symbol_first: bool,
+    // ¤ #,##0.00 or #,##0.00 ¤, always spaced when the symbol ends with a letter
+    spaced: bool,
+    // ¤-#,##0.00 instead of -¤ #,##0.00 for the negative amounts
+    minus_after_symbol: bool,
+}
+
+const fn locale(
+    tag: &'static str,
+    decimal: char,
+    group: char,
+    symbol_first: bool,
+    spaced: bool,
+) -> Locale {
+    Locale {
+        tag,
+        decimal,
+        group,
+        secondary_group: 3,
+        symbol_first,
+        spaced,
+        minus_after_symbol: false,
+    }
+}
+
+impl Locale {
+    const fn lakh(self) -> Locale {
+        Locale {
+            secondary_group: 2,
+            ..self
+        }
+    }
+
+    const fn minus_after_symbol(self) -> Locale {
+        Locale {
+            minus_after_symbol: true,
+            ..self
+        }
+    }
+}
+
+// a hand-picked subset of CLDR, only these locales are supported,
+// the first locale of a language is the default for the language alone or another region, e.g. de-DE for de or de-LI
+#[rustfmt::skip]
+static LOCALES: &[Locale] = &[
+    locale("en-US", '.', ',', true, false),
+    locale("en-GB", '.', ',', true, false),
+    locale("en-IN", '.', ',', true, false).lakh(),
+    locale("en-KE", '.', ',', true, false),
+    locale("en-UG", '.', ',', true, false),
+    locale("de-DE", ',', '.', false, true),
+    locale("de-AT", ',', NBSP, true, true),
+    locale("de-CH", '.', '’', true, true).minus_after_symbol(),
+    locale("fr-FR", ',', NARROW_NBSP, false, true),
+    locale("hu-HU", ',', NBSP, false, true),
+    locale("it-IT", ',', '.', false, true),
+    locale("it-CH", '.', '’', true, true).minus_after_symbol(),
+];
+
+// BCP 47 tag like de-CH, de_CH or de, case insensitive, None for the languages not in the table
+pub fn find_locale(tag: &str) -> Option<&'static Locale> {
+    let tag = tag.trim().replace('_', "-");
+    LOCALES
+        .iter()
+        .find(|l| l.tag.eq_ignore_ascii_case(&tag))
+        .or_else(|| {
+            let language = tag.split('-').next().unwrap_or_default();
+            LOCALES.iter().find(|l| {
+                l.tag
+                    .split('-')
+                    .next()
+                    .is_some_and(|prefix| prefix.eq_ignore_ascii_case(language))
+            })
+        })
+}
+
+pub fn supported_locales() -> Vec<&'static str> {
+    LOCALES.iter().map(|l| l.tag).collect()
+}
+
+// the amount is rounded half even to the minor units of the currency (the CLDR default),
+// currencies missing in the catalogue are shown with their code and all the digits
+pub fn format_amount(amount: Decimal, code: &str, locale: &Locale) -> FormattedAmount {
+    let info = currency_info(code);
+    let code = code.to_uppercase();
+    let symbol = info
+        .and_then(|i| i.symbol)
+        .map(str::to_string)
+        .unwrap_or_else(|| code.clone());
+    let amount = match info.and_then(|i| i.minor_units) {
+        Some(minor_units) => {
+            let mut rounded =
+                amount.round_dp_with_strategy(minor_units, RoundingStrategy::MidpointNearestEven);
+            rounded.rescale(minor_units);
+            rounded
+        }
+        None => amount.normalize(),
+    };
+    FormattedAmount {
+        amount,
+        currency: code,
+        locale: locale.tag.to_string(),
+        formatted: format_with(amount, &symbol, locale),
+    }
+}
+
+fn format_with(amount: Decimal, symbol: &str, locale: &Locale) -> String {
+    let number = format_number(amount.abs(), locale);
+    let minus = if amount.is_sign_negative() && !amount.is_zero() {
+        "-"
+    } else {
+        ""
+    };
+    if locale.symbol_first {
+        let space = if spaced(locale, symbol.chars().last()) {
+            NBSP.to_string()
+        } else {
+            String::new()
+        };
+        if locale.minus_after_symbol && !minus.is_empty() {
+            format!("{}{}{}", symbol, minus, number)
+        } else {
+            format!("{}{}{}{}", minus, symbol, space, number)
+        }
+    } else {
+        let space = if spaced(locale, symbol.chars().next()) {
+            NBSP.to_string()
+        } else {
+            String::new()
+        };
+        format!("{}{}{}{}", minus, number, space, symbol)
+    }
+}
+
+// CLDR currency spacing: a symbol is separated from the digits unless it is a currency sign like $ or €
+fn spaced(locale: &Locale, adjacent: Option<char>) -> bool {
+    locale.spaced || adjacent.is_some_and(char::is_alphabetic)
+}
+
+fn format_number(amount: Decimal, locale: &Locale) -> String {
+    let text = amount.to_string();
+    let (integer, fraction) = match text.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (text.as_str(), None),
+    };
+    let mut grouped = group(integer, locale);
+    if let Some(fraction) = fraction {
+        grouped.push(locale.decimal);
+        grouped.push_str(fraction);
+    }
+    grouped
+}
+
+// groups of three for the last digits, the secondary group size before
+fn group(digits: &str, locale: &Locale) -> String {
+    if digits.len() <= 3 {
+        return digits.to_string();
+    }
+    let (head, last) = digits.split_at(digits.len() - 3);
+    let mut groups = vec![last];
+    let mut rest = head;
+    while rest.len() > locale.secondary_group {
+        let (head, group) = rest.split_at(rest.len() - locale.secondary_group);
+        groups.push(group);
+        rest = head;
+    }
+    groups.push(rest);
+    groups.reverse();
+    groups.join(&locale.group.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn format(amount: Decimal, code: &str, tag: &str) -> String {
+        format_amount(amount, code, find_locale(tag).unwrap())
+            .formatted
+            .replace([NBSP, NARROW_NBSP], " ")
+    }
+
+    #[test]
+    fn test_find_locale() {
+        assert_eq!(find_locale("de-CH").unwrap().tag, "de-CH");
+        assert_eq!(find_locale("de_ch").unwrap().tag, "de-CH");
+        assert_eq!(find_locale("de").unwrap().tag, "de-DE");
+        assert_eq!(find_locale("fr-BE").unwrap().tag, "fr-FR");
+        assert!(find_locale("xx-YY").is_none());
+        assert!(find_locale("").is_none());
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format(dec!(1299), "CHF", "de-CH"), "CHF 1’299.00");
+        assert_eq!(format(dec!(12345), "KES", "en-KE"), "KSh 12,345.00");
+        assert_eq!(format(dec!(1299), "EUR", "de-DE"), "1.299,00 €");
+        assert_eq!(format(dec!(1299.5), "EUR", "fr-FR"), "1 299,50 €");
+        assert_eq!(format(dec!(1234567.891), "USD", "en-US"), "$1,234,567.89");
+        assert_eq!(format(dec!(12345.5), "UGX", "en-UG"), "USh 12,346");
+        assert_eq!(format(dec!(1234567), "INR", "en-IN"), "₹12,34,567.00");
+        assert_eq!(format(dec!(1299), "HUF", "hu-HU"), "1 299,00 Ft");
+        assert_eq!(format(dec!(0.5), "EUR", "de-AT"), "€ 0,50");
+    }
+
+    #[test]
+    fn test_format_negative_amount() {
+        assert_eq!(format(dec!(-1299), "CHF", "de-CH"), "CHF-1’299.00");
+        assert_eq!(format(dec!(-5), "USD", "en-US"), "-$5.00");
+        assert_eq!(format(dec!(-5), "EUR", "de-DE"), "-5,00 €");
+    }
+
+    #[test]
+    fn test_format_outside_of_catalogue() {
+        let formatted = format_amount(dec!(0.00123400), "btc", find_locale("en").unwrap());
+
+        assert_eq!(formatted.currency, "BTC");
+        assert_eq!(formatted.formatted, "BTC\u{a0}0.001234");
+    }
+}
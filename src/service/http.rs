@@ -0,0 +1,365 @@
+use actix_web::rt::time::sleep;
+use log::{info, warn};
+use reqwest::header::{HeaderMap, ACCEPT, RETRY_AFTER};
+use reqwest::{Client, Response, StatusCode};
+use std::env;
+use std::sync::LazyLock;
+use std::time::Duration;
+use time::format_description::well_known::Rfc2822;
+use time::OffsetDateTime;
+
+// shared HTTP settings for all rate providers, configurable via environment variables
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    pub connect_timeout: Duration,
+    // idle time between two reads, an upstream trickling bytes never reaches it
+    pub read_timeout: Duration,
+    // whole request from connecting to the last byte of the body
+    pub total_timeout: Duration,
+    // number of retries after the first attempt
+    pub max_retries: u32,
+    pub backoff_base: Duration,
+    // upper bound for a single wait, also the longest Retry-After we are willing to honour
+    pub backoff_max: Duration,
+    pub user_agent: String,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            connect_timeout: Duration::from_secs(5),
+            read_timeout: Duration::from_secs(10),
+            total_timeout: Duration::from_secs(30),
+            max_retries: 2,
+            backoff_base: Duration::from_millis(200),
+            backoff_max: Duration::from_secs(5),
+            user_agent: format!("exchange-rate-service/{}", env!("CARGO_PKG_VERSION")),
+        }
+    }
+}
+
+impl HttpConfig {
+    pub fn from_env() -> Self {
+        let default = HttpConfig::default();
+        HttpConfig {
+            connect_timeout: env_millis("HTTP_CONNECT_TIMEOUT_MS")
+                .unwrap_or(default.connect_timeout),
+            read_timeout: env_millis("HTTP_READ_TIMEOUT_MS").unwrap_or(default.read_timeout),
+            total_timeout: env_millis("HTTP_TOTAL_TIMEOUT_MS").unwrap_or(default.total_timeout),
+            max_retries: env::var("HTTP_MAX_RETRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default.max_retries),
+            backoff_base: env_millis("HTTP_BACKOFF_BASE_MS").unwrap_or(default.backoff_base),
+            backoff_max: env_millis("HTTP_BACKOFF_MAX_MS").unwrap_or(default.backoff_max),
+            user_agent: env::var("HTTP_USER_AGENT").unwrap_or(default.user_agent),
+        }
+    }
+}
+
+fn env_millis(key: &str) -> Option<Duration> {
+    env::var(key)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_millis)
+}
+
+pub struct HttpClient {
+    client: Client,
+    config: HttpConfig,
+}
+
+impl HttpClient {
+    pub fn new(config: HttpConfig) -> Self {
+        let client = Client::builder()
+            .connect_timeout(config.connect_timeout)
+            .read_timeout(config.read_timeout)
+            .timeout(config.total_timeout)
+            .user_agent(config.user_agent.as_str())
+            .build()
+            .expect("failed to build the HTTP client");
+        HttpClient { client, config }
+    }
+
+    // idempotent GET, retried with jittered exponential backoff on transport errors, 5xx and 429
+    // when the retries are exhausted the last response is returned, so callers can inspect the status
+    pub async fn get(&self, url: &str) -> reqwest::Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .client
+                .get(url)
+                .header(ACCEPT, "application/json")
+                .send()
+                .await;
+            let wait = match &result {
+                Ok(reply) if is_retryable_status(reply.status()) => {
+                    match retry_after(reply.headers(), OffsetDateTime::now_utc()) {
+                        Some(wait) if wait > self.config.backoff_max => {
+                            warn!(
+                                "{} replied {}, Retry-After {:?} is too long, giving up",
+                                url,
+                                reply.status(),
+                                wait
+                            );
+                            return result;
+                        }
+                        Some(wait) => wait,
+                        None => self.backoff(attempt),
+                    }
+                }
+                Err(e) if is_retryable_error(e) => self.backoff(attempt),
+                _ => return result,
+            };
+            if attempt >= self.config.max_retries {
+                return result;
+            }
+            attempt += 1;
+            match &result {
+                Ok(reply) => warn!(
+                    "{} replied {}, retry {}/{} in {:?}",
+                    url,
+                    reply.status(),
+                    attempt,
+                    self.config.max_retries,
+                    wait
+                ),
+                Err(e) => warn!(
+                    "{} failed with {}, retry {}/{} in {:?}",
+                    url, e, attempt, self.config.max_retries, wait
+                ),
+            }
+            sleep(wait).await;
+        }
+    }
+
+    // exponential backoff with equal jitter, half of the delay is fixed and half is random
+    fn backoff(&self, attempt: u32) -> Duration {
+        let delay = backoff_delay(&self.config, attempt);
+        let half = delay / 2;
+        half + half.mul_f64(fastrand::f64())
+    }
+}
+
+fn backoff_delay(config: &HttpConfig, attempt: u32) -> Duration {
+    config
+        .backoff_base
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(config.backoff_max)
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request()
+}
+
+// Retry-After is either delay in seconds or an HTTP date
+fn retry_after(headers: &HeaderMap, now: OffsetDateTime) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = OffsetDateTime::parse(value, &Rfc2822).ok()?;
+    Some(Duration::try_from(at - now).unwrap_or(Duration::ZERO))
+}
+
+static HTTP_CLIENT: LazyLock<HttpClient> = LazyLock::new(|| {
+    let config = HttpConfig::from_env();
+    info!("http client: {:?}", config);
+    HttpClient::new(config)
+});
+
+// shared by all providers, keeps the connection pool and settings in one place
+pub fn http_client() -> &'static HttpClient {
+    &HTTP_CLIENT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use time::macros::datetime;
+
+    fn test_config() -> HttpConfig {
+        HttpConfig {
+            connect_timeout: Duration::from_millis(500),
+            read_timeout: Duration::from_millis(500),
+            total_timeout: Duration::from_secs(2),
+            max_retries: 2,
+            backoff_base: Duration::from_millis(10),
+            backoff_max: Duration::from_millis(50),
+            user_agent: "test-agent".to_string(),
+        }
+    }
+
+    // replies with the given raw HTTP responses, one per connection, returns the base url
+    fn serve(replies: Vec<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for reply in replies {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                stream.write_all(reply.as_bytes()).unwrap();
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_retry_after_seconds() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+        let now = datetime!(2024-11-12 10:00:00 UTC);
+        assert_eq!(retry_after(&headers, now), Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn test_retry_after_http_date() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Tue, 12 Nov 2024 10:00:30 GMT"),
+        );
+        let now = datetime!(2024-11-12 10:00:00 UTC);
+        assert_eq!(retry_after(&headers, now), Some(Duration::from_secs(30)));
+        // dates in the past mean retry immediately
+        let later = datetime!(2024-11-12 11:00:00 UTC);
+        assert_eq!(retry_after(&headers, later), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_retry_after_missing_or_invalid() {
+        let now = datetime!(2024-11-12 10:00:00 UTC);
+        assert_eq!(retry_after(&HeaderMap::new(), now), None);
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers, now), None);
+    }
+
+    #[test]
+    fn test_backoff_is_exponential_and_bounded() {
+        let config = test_config();
+        assert_eq!(backoff_delay(&config, 0), Duration::from_millis(10));
+        assert_eq!(backoff_delay(&config, 1), Duration::from_millis(20));
+        assert_eq!(backoff_delay(&config, 2), Duration::from_millis(40));
+        assert_eq!(backoff_delay(&config, 3), Duration::from_millis(50));
+        assert_eq!(backoff_delay(&config, 40), Duration::from_millis(50));
+
+        let client = HttpClient::new(config);
+        for attempt in 0..5 {
+            let wait = client.backoff(attempt);
+            let delay = backoff_delay(&client.config, attempt);
+            assert!(wait >= delay / 2 && wait <= delay);
+        }
+    }
+
+    #[test]
+    fn test_retryable_status() {
+        assert!(is_retryable_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable_status(StatusCode::OK));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+    }
+
+    #[actix_web::test]
+    async fn test_get_retries_server_errors() {
+        let url = serve(vec![
+            "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}",
+        ]);
+        let client = HttpClient::new(test_config());
+
+        let reply = client.get(&url).await.unwrap();
+
+        assert_eq!(reply.status(), StatusCode::OK);
+        assert_eq!(reply.text().await.unwrap(), "{}");
+    }
+
+    #[actix_web::test]
+    async fn test_get_returns_last_reply_when_retries_exhausted() {
+        let url = serve(vec![
+            "HTTP/1.1 429 Too Many Requests\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 429 Too Many Requests\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 429 Too Many Requests\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ]);
+        let client = HttpClient::new(test_config());
+
+        let reply = client.get(&url).await.unwrap();
+
+        assert_eq!(reply.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[actix_web::test]
+    async fn test_get_gives_up_on_long_retry_after() {
+        let url = serve(vec![
+            "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 3600\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ]);
+        let client = HttpClient::new(test_config());
+
+        let reply = client.get(&url).await.unwrap();
+
+        assert_eq!(reply.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[actix_web::test]
+    async fn test_get_times_out_on_hanging_upstream() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        // accept connections but never answer
+        thread::spawn(move || {
+            let mut streams = Vec::new();
+            for stream in listener.incoming() {
+                streams.push(stream);
+            }
+        });
+        let mut config = test_config();
+        config.read_timeout = Duration::from_millis(100);
+        config.max_retries = 1;
+        let client = HttpClient::new(config);
+
+        let result = client.get(&url).await;
+
+        assert!(result.unwrap_err().is_timeout());
+    }
+
+    #[actix_web::test]
+    async fn test_get_times_out_on_trickling_upstream() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        // one byte of the body every 50ms, never idle for the read timeout
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf);
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 1000\r\n\r\n");
+            for _ in 0..1000 {
+                if stream.write_all(b" ").is_err() {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+        });
+        let mut config = test_config();
+        config.read_timeout = Duration::from_millis(200);
+        config.total_timeout = Duration::from_millis(300);
+        config.max_retries = 0;
+        let client = HttpClient::new(config);
+
+        let result = match client.get(&url).await {
+            Ok(reply) => reply.text().await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+
+        assert!(result.unwrap_err().is_timeout());
+    }
+}
@@ -0,0 +1,171 @@
+use crate::route::model::ExchangeRate;
+use crate::service::currency::currency_info;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use time::macros::date;
+use time::Date;
+
+// withdrawn currency replaced at a fixed conversion factor, e.g. 1 EUR = 1.95583 DEM
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Legacy {
+    pub code: &'static str,
+    pub successor: &'static str,
+    // units of the legacy currency for 1 unit of the successor
+    pub factor: Decimal,
+    // first day of the fixed factor, the legacy currency was floating before
+    pub since: Date,
+}
+
+const fn legacy(
+    code: &'static str,
+    successor: &'static str,
+    factor: Decimal,
+    since: Date,
+) -> Legacy {
+    Legacy {
+        code,
+        successor,
+        factor,
+        since,
+    }
+}
+
+#[rustfmt::skip]
+static LEGACIES: &[Legacy] = &[
+    legacy("DEM", "EUR", Decimal::from_parts(195583, 0, 0, false, 5), date!(1999 - 01 - 01)),
+    legacy("FRF", "EUR", Decimal::from_parts(655957, 0, 0, false, 5), date!(1999 - 01 - 01)),
+    legacy("GHC", "GHS", Decimal::from_parts(10000, 0, 0, false, 0), date!(2007 - 07 - 01)),
+    legacy("MZM", "MZN", Decimal::from_parts(1000, 0, 0, false, 0), date!(2006 - 07 - 01)),
+    legacy("ZMK", "ZMW", Decimal::from_parts(1000, 0, 0, false, 0), date!(2013 - 01 - 01)),
+];
+
+pub fn legacies() -> &'static [Legacy] {
+    LEGACIES
+}
+
+pub fn find_legacy(code: &str) -> Option<&'static Legacy> {
+    LEGACIES.iter().find(|l| l.code.eq_ignore_ascii_case(code))
+}
+
+impl Legacy {
+    pub fn name(&self) -> &'static str {
+        currency_info(self.code).map_or(self.code, |info| info.name)
+    }
+
+    pub fn is_fixed_at(&self, date: Date) -> bool {
+        self.since <= date
+    }
+}
+
+// adds the legacy currencies fixed at the date as counters, derived from the rate of their successor
+pub fn with_legacies(rates: ExchangeRate, date: Date) -> ExchangeRate {
+    let mut rates = rates;
+    let derived = LEGACIES
+        .iter()
+        .filter(|l| l.is_fixed_at(date) && l.code != rates.base)
+        .filter_map(|l| {
+            let successor = if l.successor == rates.base {
+                Decimal::ONE
+            } else {
+                *rates.rates.get(l.successor)?
+            };
+            Some((l, successor * l.factor))
+        })
+        .collect::<Vec<_>>();
+    // quoted on the day of the successor
+    for (legacy, rate) in derived {
+        if let Some(date) = rates.dates.get(legacy.successor).copied() {
+            rates.dates.insert(legacy.code.to_string(), date);
+        }
+        rates.rates.insert(legacy.code.to_string(), rate);
+    }
+    rates
+}
+
+// rates of the legacy currency as base, from the rates of the successor, with all the digits
+pub fn legacy_rates(legacy: &Legacy, successor: ExchangeRate) -> ExchangeRate {
+    let mut rates: HashMap<String, Decimal> = successor
+        .rates
+        .into_iter()
+        .filter(|(counter, _)| counter != legacy.code)
+        .map(|(counter, rate)| (counter, rate / legacy.factor))
+        .collect();
+    rates.insert(legacy.successor.to_string(), Decimal::ONE / legacy.factor);
+    ExchangeRate {
+        base: legacy.code.to_string(),
+        rates,
+        overridden: successor.overridden,
+        dates: successor.dates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn eur() -> ExchangeRate {
+        ExchangeRate {
+            base: "EUR".to_string(),
+            overridden: Vec::new(),
+            dates: HashMap::new(),
+            rates: HashMap::from([
+                ("USD".to_string(), dec!(1.05)),
+                ("CHF".to_string(), dec!(0.95)),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_find_legacy() {
+        let dem = find_legacy("dem").unwrap();
+
+        assert_eq!(dem.successor, "EUR");
+        assert_eq!(dem.factor, dec!(1.95583));
+        assert_eq!(dem.name(), "Deutsche Mark");
+        assert_eq!(find_legacy("FRF").unwrap().factor, dec!(6.55957));
+        assert_eq!(find_legacy("ZMK").unwrap().factor, dec!(1000));
+        assert!(find_legacy("EUR").is_none());
+    }
+
+    #[test]
+    fn test_with_legacies() {
+        let rates = with_legacies(eur(), date!(2024 - 11 - 12));
+
+        assert_eq!(rates.rates["DEM"], dec!(1.95583));
+        assert_eq!(rates.rates["FRF"], dec!(6.55957));
+        assert!(!rates.rates.contains_key("GHC"));
+
+        let usd = ExchangeRate {
+            base: "USD".to_string(),
+            overridden: Vec::new(),
+            dates: HashMap::new(),
+            rates: HashMap::from([("GHS".to_string(), dec!(15.5))]),
+        };
+        assert_eq!(
+            with_legacies(usd.clone(), date!(2024 - 11 - 12)).rates["GHC"],
+            dec!(155000)
+        );
+        // floating before the redenomination
+        assert!(!with_legacies(usd, date!(2007 - 06 - 30))
+            .rates
+            .contains_key("GHC"));
+    }
+
+    #[test]
+    fn test_legacy_rates() {
+        let dem = find_legacy("DEM").unwrap();
+        let eur = with_legacies(eur(), date!(2024 - 11 - 12));
+
+        let rates = legacy_rates(dem, eur);
+
+        assert_eq!(rates.base, "DEM");
+        assert_eq!(rates.rates["EUR"], dec!(0.5112918811962184852466727681));
+        assert_eq!(rates.rates["USD"], dec!(0.5368564752560294095090064065));
+        assert_eq!(rates.rates["FRF"], dec!(3.3538548851382788892695172893));
+        assert!(!rates.rates.contains_key("DEM"));
+        // the factor is known even when the providers are down
+        let fixed = legacy_rates(dem, ExchangeRate::empty("EUR"));
+        assert_eq!(fixed.rates.len(), 1);
+    }
+}
@@ -0,0 +1,134 @@
+use prometheus::{
+    register_gauge_vec, register_histogram_vec, register_int_counter_vec, GaugeVec, HistogramVec,
+    IntCounterVec,
+};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use time::OffsetDateTime;
+
+// service level metrics, registered in the default prometheus registry and exposed on /metrics
+
+static CACHE_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "cache_requests_total",
+        "Cache lookups by cache and result (hit or miss)",
+        &["cache", "result"]
+    )
+    .unwrap()
+});
+
+static UPSTREAM_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "upstream_requests_total",
+        "Calls to the rate providers",
+        &["provider", "operation"]
+    )
+    .unwrap()
+});
+
+static UPSTREAM_ERRORS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "upstream_errors_total",
+        "Failed calls to the rate providers",
+        &["provider", "operation"]
+    )
+    .unwrap()
+});
+
+static UPSTREAM_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "upstream_request_duration_seconds",
+        "Latency of the rate provider calls, including retries",
+        &["provider", "operation"],
+        vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0]
+    )
+    .unwrap()
+});
+
+static RATES_AGE: LazyLock<GaugeVec> = LazyLock::new(|| {
+    register_gauge_vec!(
+        "rates_age_seconds",
+        "Age of the freshest latest rates per base currency",
+        &["base"]
+    )
+    .unwrap()
+});
+
+// base currency -> when the latest rates were fetched from the providers
+static RATES_FETCHED_AT: LazyLock<Mutex<HashMap<String, OffsetDateTime>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub fn record_cache_lookup(cache: &str, was_cached: bool) {
+    let result = if was_cached { "hit" } else { "miss" };
+    CACHE_REQUESTS.with_label_values(&[cache, result]).inc();
+}
+
+pub fn record_upstream_call(provider: &str, operation: &str, latency: Duration, failed: bool) {
+    let labels = [provider, operation];
+    UPSTREAM_REQUESTS.with_label_values(&labels).inc();
+    UPSTREAM_DURATION
+        .with_label_values(&labels)
+        .observe(latency.as_secs_f64());
+    if failed {
+        UPSTREAM_ERRORS.with_label_values(&labels).inc();
+    }
+}
+
+pub fn record_rates_fetched(base: &str) {
+    RATES_FETCHED_AT
+        .lock()
+        .unwrap()
+        .insert(base.to_string(), OffsetDateTime::now_utc());
+}
+
+// the age is a function of time, it is calculated when the metrics are scraped
+pub fn refresh_rates_age() {
+    let now = OffsetDateTime::now_utc();
+    for (base, fetched_at) in RATES_FETCHED_AT.lock().unwrap().iter() {
+        RATES_AGE
+            .with_label_values(&[base.as_str()])
+            .set((now - *fetched_at).as_seconds_f64());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_cache_lookup() {
+        let hits = CACHE_REQUESTS.with_label_values(&["test_cache", "hit"]);
+        let misses = CACHE_REQUESTS.with_label_values(&["test_cache", "miss"]);
+
+        record_cache_lookup("test_cache", false);
+        record_cache_lookup("test_cache", true);
+        record_cache_lookup("test_cache", true);
+
+        assert_eq!(hits.get(), 2);
+        assert_eq!(misses.get(), 1);
+    }
+
+    #[test]
+    fn test_record_upstream_call() {
+        record_upstream_call("Test Provider", "latest", Duration::from_millis(100), false);
+        record_upstream_call("Test Provider", "latest", Duration::from_millis(300), true);
+
+        let labels = ["Test Provider", "latest"];
+        assert_eq!(UPSTREAM_REQUESTS.with_label_values(&labels).get(), 2);
+        assert_eq!(UPSTREAM_ERRORS.with_label_values(&labels).get(), 1);
+        let histogram = UPSTREAM_DURATION.with_label_values(&labels);
+        assert_eq!(histogram.get_sample_count(), 2);
+        assert!((histogram.get_sample_sum() - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rates_age() {
+        record_rates_fetched("XTS");
+
+        refresh_rates_age();
+
+        let age = RATES_AGE.with_label_values(&["XTS"]).get();
+        assert!((0.0..5.0).contains(&age));
+    }
+}
@@ -1,4 +1,17 @@
+pub mod basket;
+mod circuit_breaker;
+pub mod conversion;
+pub mod currency;
+pub mod currency_names;
+pub mod format;
+mod http;
+pub mod legacy;
+pub mod metrics;
 pub mod provider;
 mod provider_float;
 mod provider_frankfurter_v2;
 mod provider_free;
+pub mod provider_override;
+pub mod series;
+pub mod spread;
+mod stats;
@@ -1,39 +1,244 @@
+use actix_web::rt::time::sleep;
 use async_trait::async_trait;
 use cached::proc_macro::cached;
+use cached::Cached;
+use cached::Return;
 use futures::future::join_all;
-use log::info;
-use std::collections::HashMap;
+use log::{info, warn};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::LazyLock;
-use time::Date;
+use std::time::{Duration, Instant};
+use time::{Date, OffsetDateTime};
 
-use crate::route::model::ExchangeRate;
+use crate::route::model::{ExchangeRate, ProviderStatus, ProviderSymbol, SymbolConflict};
+use crate::service::basket::{basket_rates, baskets, find_basket, with_baskets};
+use crate::service::circuit_breaker::CircuitBreaker;
+use crate::service::legacy::{find_legacy, legacies, legacy_rates, with_legacies};
+use crate::service::metrics;
 use crate::service::provider_float::FloatRateProvider;
 use crate::service::provider_frankfurter_v2::FrankfurterV2RateProvider;
 use crate::service::provider_free::FreeRateProvider;
+use crate::service::provider_override::{crossed_validity, OverrideRateProvider};
+use crate::service::stats::ProviderStats;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProviderError {
+    // transport failure or unexpected status code
+    Request(String),
+    // the reply can't be interpreted
+    Parse(String),
+    // quota exhausted, the provider replied with 429
+    RateLimited(String),
+    // the provider is up but doesn't serve the request, e.g. 404 for a base it doesn't know
+    Unsupported(String),
+}
+
+impl ProviderError {
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, ProviderError::RateLimited(_))
+    }
+
+    // neither trips the circuit nor counts as an error of the provider
+    pub fn is_unsupported(&self) -> bool {
+        matches!(self, ProviderError::Unsupported(_))
+    }
+}
+
+impl ProviderError {
+    // only the replies telling there is no data for the request are neutral, other client errors
+    // (revoked key, changed api) are failures of the provider
+    fn from_status(status: reqwest::StatusCode, message: String) -> Self {
+        match status {
+            reqwest::StatusCode::TOO_MANY_REQUESTS => ProviderError::RateLimited(message),
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::UNPROCESSABLE_ENTITY => {
+                ProviderError::Unsupported(message)
+            }
+            _ => ProviderError::Request(message),
+        }
+    }
+}
+
+impl From<reqwest::Error> for ProviderError {
+    fn from(e: reqwest::Error) -> Self {
+        match e.status() {
+            Some(status) => ProviderError::from_status(status, e.to_string()),
+            None if e.is_decode() => ProviderError::Parse(e.to_string()),
+            None => ProviderError::Request(e.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProviderError::Request(message) => write!(f, "request failed: {}", message),
+            ProviderError::Parse(message) => write!(f, "invalid reply: {}", message),
+            ProviderError::RateLimited(message) => write!(f, "rate limited: {}", message),
+            ProviderError::Unsupported(message) => write!(f, "not supported: {}", message),
+        }
+    }
+}
+
+pub type ProviderResult<T> = Result<T, ProviderError>;
 
 // generic contract what needs to be implemented by any rate provider
 #[async_trait]
 pub trait RateProvider: Sync + Send {
     fn provider_name(&self) -> &str;
 
-    async fn latest(&self, base: &str) -> ExchangeRate;
+    // served from configuration instead of an upstream api, its dates don't make a time series on their own
+    fn is_virtual(&self) -> bool {
+        false
+    }
+
+    async fn latest(&self, base: &str) -> ProviderResult<ExchangeRate>;
 
     // iso3 -> description
-    async fn symbols(&self) -> HashMap<String, String>;
+    async fn symbols(&self) -> ProviderResult<HashMap<String, String>>;
+
+    async fn historical(
+        &self,
+        base: &str,
+        from: &Date,
+        to: &Date,
+    ) -> ProviderResult<HashMap<Date, ExchangeRate>>;
+}
+
+// wraps a provider with a circuit breaker and call statistics,
+// failures are logged and turned into empty results, so the remaining providers can still fill the gaps
+pub struct GuardedProvider {
+    provider: Box<dyn RateProvider>,
+    breaker: CircuitBreaker,
+    stats: ProviderStats,
+}
+
+impl GuardedProvider {
+    pub fn new(provider: Box<dyn RateProvider>) -> Self {
+        GuardedProvider {
+            provider,
+            breaker: CircuitBreaker::from_env(),
+            stats: ProviderStats::default(),
+        }
+    }
+
+    pub fn provider_name(&self) -> &str {
+        self.provider.provider_name()
+    }
+
+    pub fn is_virtual(&self) -> bool {
+        self.provider.is_virtual()
+    }
 
-    async fn historical(&self, base: &str, from: &Date, to: &Date) -> HashMap<Date, ExchangeRate>;
+    pub async fn latest(&self, base: &str) -> ExchangeRate {
+        self.call("latest", self.provider.latest(base), |reply| {
+            Some(reply.rates.len())
+        })
+        .await
+        .unwrap_or_else(|| ExchangeRate::empty(base))
+    }
+
+    pub async fn symbols(&self) -> HashMap<String, String> {
+        self.call("symbols", self.provider.symbols(), |reply| {
+            Some(reply.len())
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    pub async fn historical(
+        &self,
+        base: &str,
+        from: &Date,
+        to: &Date,
+    ) -> HashMap<Date, ExchangeRate> {
+        self.call(
+            "historical",
+            self.provider.historical(base, from, to),
+            |_| None,
+        )
+        .await
+        .unwrap_or_default()
+    }
+
+    pub fn last_success(&self) -> Option<OffsetDateTime> {
+        self.stats.last_success()
+    }
+
+    pub fn status(&self) -> ProviderStatus {
+        let stats = self.stats.snapshot();
+        ProviderStatus {
+            name: self.provider_name().to_string(),
+            last_success: stats.last_success,
+            last_error: stats.last_error,
+            latency: stats.latency,
+            currencies: stats.currencies,
+            circuit: self.breaker.status(),
+            quota: stats.quota,
+        }
+    }
+
+    // currencies counts the currencies in the reply, when it is meaningful for the operation
+    async fn call<T>(
+        &self,
+        operation: &str,
+        request: impl Future<Output = ProviderResult<T>>,
+        currencies: impl Fn(&T) -> Option<usize>,
+    ) -> Option<T> {
+        if !self.breaker.try_acquire() {
+            info!(
+                "{} {} skipped, circuit is open",
+                self.provider_name(),
+                operation
+            );
+            return None;
+        }
+        #[cfg(feature = "otel")]
+        let request = tracing::Instrument::instrument(
+            request,
+            tracing::info_span!("provider call", provider = self.provider_name(), operation),
+        );
+        let started = Instant::now();
+        let result = request.await;
+        let latency = started.elapsed();
+        let failed = result.as_ref().is_err_and(|e| !e.is_unsupported());
+        metrics::record_upstream_call(self.provider_name(), operation, latency, failed);
+        match result {
+            Ok(reply) => {
+                self.breaker.on_success();
+                self.stats.record_success(latency, currencies(&reply));
+                Some(reply)
+            }
+            Err(e) if e.is_unsupported() => {
+                info!("{} {} skipped, {}", self.provider_name(), operation, e);
+                self.breaker.on_neutral();
+                None
+            }
+            Err(e) => {
+                warn!("{} {} failed: {}", self.provider_name(), operation, e);
+                self.breaker.on_failure();
+                self.stats
+                    .record_failure(latency, e.to_string(), e.is_rate_limited());
+                None
+            }
+        }
+    }
 }
 
-type Providers = Vec<Box<dyn RateProvider>>;
+type Providers = Vec<GuardedProvider>;
 
 fn get_providers() -> &'static Providers {
     static PROVIDERS: LazyLock<Providers, fn() -> Providers> = LazyLock::new(|| {
         // sequence is important, earlier providers keep priority for the same currencies
         // while later providers fill gaps
         let providers: Providers = vec![
-            Box::new(FrankfurterV2RateProvider::new()),
-            Box::new(FloatRateProvider::new()),
-            Box::new(FreeRateProvider::new()),
+            GuardedProvider::new(Box::new(OverrideRateProvider::new())),
+            GuardedProvider::new(Box::new(FrankfurterV2RateProvider::new())),
+            GuardedProvider::new(Box::new(FloatRateProvider::new())),
+            GuardedProvider::new(Box::new(FreeRateProvider::new())),
         ];
         info!(
             "providers: {:?}",
@@ -51,9 +256,123 @@ pub fn count_providers() -> usize {
     get_providers().len()
 }
 
-#[cached(time = 3600, sync_writes = "default")]
+pub fn provider_statuses() -> Vec<ProviderStatus> {
+    get_providers().iter().map(|p| p.status()).collect()
+}
+
+// most recent successful call of any upstream provider
+pub fn last_provider_success() -> Option<OffsetDateTime> {
+    get_providers()
+        .iter()
+        .filter(|p| !p.is_virtual())
+        .filter_map(|p| p.last_success())
+        .max()
+}
+
+static CACHE_WARMED: AtomicBool = AtomicBool::new(false);
+
+// set once the latest rates of any base currency were cached
+pub fn is_cache_warmed() -> bool {
+    CACHE_WARMED.load(Ordering::Relaxed)
+}
+
+// fills the cache at startup and refreshes it periodically,
+// so the first requests are fast and readiness reflects the providers
+pub async fn keep_warm() {
+    let bases = env::var("WARM_UP_BASES")
+        .unwrap_or_else(|_| "CHF,EUR,USD".to_string())
+        .split(',')
+        .map(|base| base.trim().to_uppercase())
+        .filter(|base| !base.is_empty())
+        .collect::<Vec<_>>();
+    let interval = env::var("WARM_UP_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(1800));
+    loop {
+        info!("warming up the cache for {:?}", bases);
+        // replaces the cached entries, a lookup would be a hit until the entries expire
+        for base in &bases {
+            let base = quoted_currency(base);
+            let rates = fetch_rates_of(&base).await;
+            refresh_entry(&mut *CACHED_RATES_OF.lock().await, base, rates);
+        }
+        let symbols = fetch_symbols().await;
+        if !symbols.names.is_empty() {
+            CACHED_SYMBOLS
+                .lock()
+                .await
+                .cache_set((), Return::new(symbols));
+        }
+        sleep(interval).await;
+    }
+}
+
+// rates without any provider quote (all of them down) keep the warm entry until it expires
+fn refresh_entry<C>(cache: &mut C, base: String, rates: ExchangeRate)
+where
+    C: Cached<String, Return<ExchangeRate>>,
+{
+    if rates.rates.len() > rates.overridden.len() {
+        cache.cache_set(base, Return::new(rates));
+    }
+}
+
+#[cfg_attr(
+    feature = "otel",
+    tracing::instrument(name = "cache rates_of", fields(cache_hit))
+)]
 pub async fn rates_of(base: String) -> ExchangeRate {
-    rates_of_with(&base, get_providers).await
+    let today = OffsetDateTime::now_utc().date();
+    // the cached rates don't know about the overrides starting or ending today
+    if crossed_validity(today) {
+        invalidate_rates().await;
+    }
+    let rates = cached_rates_of(quoted_currency(&base)).await;
+    metrics::record_cache_lookup("rates_of", rates.was_cached);
+    #[cfg(feature = "otel")]
+    tracing::Span::current().record("cache_hit", rates.was_cached);
+    derived_rates(&base, rates.value, today).unwrap_or_else(|| ExchangeRate::empty(&base))
+}
+
+// the rates of a basket are derived from the rates of its anchor currency,
+// the rates of a legacy currency from the rates of its successor
+fn quoted_currency(base: &str) -> String {
+    match (find_legacy(base), find_basket(base)) {
+        (Some(legacy), _) => legacy.successor.to_string(),
+        (None, Some(basket)) => basket.anchor().to_string(),
+        (None, None) => base.to_string(),
+    }
+}
+
+// adds the baskets and legacy currencies to the rates of the quoted currency,
+// none for a legacy currency before its redenomination
+fn derived_rates(base: &str, quoted: ExchangeRate, date: Date) -> Option<ExchangeRate> {
+    let rates = match find_basket(base) {
+        Some(basket) => basket_rates(basket, quoted, baskets()),
+        None => with_baskets(quoted, baskets()),
+    };
+    let rates = with_legacies(rates, date);
+    match find_legacy(base) {
+        Some(legacy) if legacy.is_fixed_at(date) => Some(legacy_rates(legacy, rates)),
+        Some(_) => None,
+        None => Some(rates),
+    }
+}
+
+#[cached(time = 3600, sync_writes = "default", with_cached_flag = true)]
+async fn cached_rates_of(base: String) -> Return<ExchangeRate> {
+    Return::new(fetch_rates_of(&base).await)
+}
+
+async fn fetch_rates_of(base: &str) -> ExchangeRate {
+    let rates = rates_of_with(base, get_providers).await;
+    if rates.rates.len() > rates.overridden.len() {
+        CACHE_WARMED.store(true, Ordering::Relaxed);
+        metrics::record_rates_fetched(base);
+    }
+    rates
 }
 
 async fn rates_of_with<F>(base: &str, providers_fn: F) -> ExchangeRate
@@ -67,24 +386,166 @@ where
         .fold(ExchangeRate::empty(base), |acc, current| current.chain(acc))
 }
 
+// the overrides apply immediately instead of after the expiry of the cached rates
+pub async fn invalidate_rates() {
+    CACHED_RATES_OF.lock().await.cache_clear();
+    CACHED_HISTORICAL_RATES_OF.lock().await.cache_clear();
+}
+
 // map of ISO3 code -> description
-#[cached(time = 3600, sync_writes = "default")]
+#[cfg_attr(
+    feature = "otel",
+    tracing::instrument(name = "cache symbols", fields(cache_hit))
+)]
 pub async fn symbols() -> HashMap<String, String> {
-    join_all(get_providers().iter().map(|p| p.symbols()))
-        .await
-        .into_iter()
-        .flat_map(|symbols| symbols.into_iter())
-        .collect()
+    let mut names = provider_symbols().await;
+    // the baskets and legacy currencies are quoted even when the providers don't know them
+    for basket in baskets() {
+        names
+            .entry(basket.code.clone())
+            .or_insert_with(|| basket.name());
+    }
+    for legacy in legacies() {
+        names
+            .entry(legacy.code.to_string())
+            .or_insert_with(|| legacy.name().to_string());
+    }
+    names
+}
+
+// the names delivered by the providers only, empty when all of them are down
+pub async fn provider_symbols() -> HashMap<String, String> {
+    merged_symbols().await.names
+}
+
+// codes named differently by the providers, the name of the first provider is used
+pub async fn symbol_conflicts() -> Vec<SymbolConflict> {
+    merged_symbols().await.conflicts
+}
+
+async fn merged_symbols() -> MergedSymbols {
+    let (symbols, was_cached) = match cached_symbols().await {
+        Ok(symbols) => (symbols.value, symbols.was_cached),
+        Err(empty) => (empty, false),
+    };
+    metrics::record_cache_lookup("symbols", was_cached);
+    #[cfg(feature = "otel")]
+    tracing::Span::current().record("cache_hit", was_cached);
+    symbols
+}
+
+#[derive(Debug, Clone, Default)]
+struct MergedSymbols {
+    names: HashMap<String, String>,
+    conflicts: Vec<SymbolConflict>,
+}
+
+// nothing is cached when all the providers are down, the next lookup tries again
+#[cached(
+    time = 3600,
+    sync_writes = "default",
+    with_cached_flag = true,
+    result = true
+)]
+async fn cached_symbols() -> Result<Return<MergedSymbols>, MergedSymbols> {
+    let merged = fetch_symbols().await;
+    if merged.names.is_empty() {
+        Err(merged)
+    } else {
+        Ok(Return::new(merged))
+    }
+}
+
+async fn fetch_symbols() -> MergedSymbols {
+    let providers = get_providers();
+    let symbols = join_all(providers.iter().map(|p| p.symbols())).await;
+    let merged = merge_symbols(providers.iter().map(|p| p.provider_name()).zip(symbols));
+    for basket in baskets() {
+        if merged.names.contains_key(&basket.code) {
+            warn!(
+                "BASKETS: {} is quoted by the providers, the rates of the basket are served instead",
+                basket.code
+            );
+        }
+    }
+    merged
 }
 
-#[cached(time = 3600, sync_writes = "default")]
+// merge with priority like the rates (earlier providers keep priority for the same currencies),
+// names differing in case or spacing only are not considered as conflicts
+fn merge_symbols<'a>(
+    symbols: impl IntoIterator<Item = (&'a str, HashMap<String, String>)>,
+) -> MergedSymbols {
+    let mut by_code: BTreeMap<String, Vec<ProviderSymbol>> = BTreeMap::new();
+    for (provider, names) in symbols {
+        for (code, name) in names {
+            let upper = code.to_uppercase();
+            let symbol = ProviderSymbol {
+                provider: provider.to_string(),
+                name: name.trim().to_string(),
+            };
+            let entries = by_code.entry(upper.clone()).or_default();
+            // a provider might deliver the same code in different cases, the uppercase one wins
+            // whatever the order of the map
+            match entries.last_mut() {
+                Some(last) if last.provider == provider => {
+                    if code == upper {
+                        *last = symbol;
+                    }
+                }
+                _ => entries.push(symbol),
+            }
+        }
+    }
+    let mut merged = MergedSymbols::default();
+    for (code, names) in by_code {
+        let chosen = names[0].name.clone();
+        if names.iter().any(|n| !same_name(&n.name, &chosen)) {
+            merged.conflicts.push(SymbolConflict {
+                code: code.clone(),
+                chosen: chosen.clone(),
+                names,
+            });
+        }
+        merged.names.insert(code, chosen);
+    }
+    merged
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.split_whitespace()
+        .map(str::to_lowercase)
+        .eq(b.split_whitespace().map(str::to_lowercase))
+}
+
+#[cfg_attr(
+    feature = "otel",
+    tracing::instrument(name = "cache historical_rates_of", fields(cache_hit))
+)]
 pub async fn historical_rates_of(
     base: String,
     from: Date,
     to: Date,
 ) -> HashMap<Date, ExchangeRate> {
+    let rates = cached_historical_rates_of(quoted_currency(&base), from, to).await;
+    metrics::record_cache_lookup("historical_rates_of", rates.was_cached);
+    #[cfg(feature = "otel")]
+    tracing::Span::current().record("cache_hit", rates.was_cached);
+    rates
+        .value
+        .into_iter()
+        .filter_map(|(date, rates)| Some((date, derived_rates(&base, rates, date)?)))
+        .collect()
+}
+
+#[cached(time = 3600, sync_writes = "default", with_cached_flag = true)]
+async fn cached_historical_rates_of(
+    base: String,
+    from: Date,
+    to: Date,
+) -> Return<HashMap<Date, ExchangeRate>> {
     info!("historical_rates_of: {} {} {}", base, from, to);
-    historical_rates_of_with(&base, from, to, get_providers).await
+    Return::new(historical_rates_of_with(&base, from, to, get_providers).await)
 }
 
 async fn historical_rates_of_with<F>(
@@ -96,28 +557,37 @@ async fn historical_rates_of_with<F>(
 where
     F: Fn() -> &'static Providers,
 {
-    let rates = join_all(
-        providers_fn()
-            .iter()
-            .map(|p| p.historical(base, &from, &to)),
-    )
-    .await
-    .into_iter()
-    .flat_map(|rates| rates.into_iter());
+    let providers = providers_fn();
+    let rates = join_all(providers.iter().map(|p| p.historical(base, &from, &to))).await;
+    // days quoted by the upstream providers, e.g. the weekends are missing
+    let quoted: HashSet<Date> = providers
+        .iter()
+        .zip(&rates)
+        .filter(|(p, _)| !p.is_virtual())
+        .flat_map(|(_, rates)| rates.keys().copied())
+        .collect();
     // merge with priority (earlier providers keep priority for the same currencies)
-    rates.fold(HashMap::new(), |mut acc, (date, current)| {
-        if let Some(existing) = acc.get_mut(&date) {
-            *existing = current.chain(existing.clone());
-        } else {
-            acc.insert(date, current);
-        }
-        acc
-    })
+    let mut merged: HashMap<Date, ExchangeRate> = rates
+        .into_iter()
+        .flat_map(|rates| rates.into_iter())
+        .fold(HashMap::new(), |mut acc, (date, current)| {
+            if let Some(existing) = acc.get_mut(&date) {
+                *existing = current.chain(existing.clone());
+            } else {
+                acc.insert(date, current);
+            }
+            acc
+        });
+    merged.retain(|date, _| quoted.contains(date));
+    merged
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::route::model::CircuitState;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
     use std::collections::HashMap;
     use std::ops::Add;
     use std::sync::OnceLock;
@@ -127,7 +597,7 @@ mod tests {
     // Mock provider for testing
     struct MockProvider {
         name: String,
-        rates: HashMap<String, f32>,
+        rates: HashMap<String, Decimal>,
     }
 
     #[allow(unused_variables)]
@@ -137,15 +607,17 @@ mod tests {
             &self.name
         }
 
-        async fn latest(&self, base: &str) -> ExchangeRate {
-            ExchangeRate {
+        async fn latest(&self, base: &str) -> ProviderResult<ExchangeRate> {
+            Ok(ExchangeRate {
                 base: base.to_string(),
+                overridden: Vec::new(),
+                dates: HashMap::new(),
                 rates: self.rates.clone(),
-            }
+            })
         }
 
-        async fn symbols(&self) -> HashMap<String, String> {
-            HashMap::new()
+        async fn symbols(&self) -> ProviderResult<HashMap<String, String>> {
+            Ok(HashMap::new())
         }
 
         async fn historical(
@@ -153,7 +625,7 @@ mod tests {
             base: &str,
             from: &Date,
             to: &Date,
-        ) -> HashMap<Date, ExchangeRate> {
+        ) -> ProviderResult<HashMap<Date, ExchangeRate>> {
             // days between from and to
             let days = to.to_julian_day() - from.to_julian_day();
             // iterate between from until to and create ExchangeRate for each day
@@ -162,51 +634,87 @@ mod tests {
                 let date = from.add(Duration::days(i as i64));
                 let exchange_rate = ExchangeRate {
                     base: base.to_string(),
+                    overridden: Vec::new(),
+                    dates: HashMap::new(),
                     // add 1 to each rate to make it different from the base
                     // and make it easier to test
                     // 1.1, 1.2, 1.3, ...
                     rates: self
                         .rates
                         .iter()
-                        .map(|(k, v)| (k.clone(), v + i as f32 + 1.0))
+                        .map(|(k, v)| (k.clone(), v + Decimal::from(i) + Decimal::ONE))
                         .collect(),
                 };
                 rates.insert(date, exchange_rate);
             }
-            rates
+            Ok(rates)
+        }
+    }
+
+    // provider which is always down
+    struct FailingProvider {}
+
+    #[allow(unused_variables)]
+    #[async_trait]
+    impl RateProvider for FailingProvider {
+        fn provider_name(&self) -> &str {
+            "Failing"
+        }
+
+        async fn latest(&self, base: &str) -> ProviderResult<ExchangeRate> {
+            Err(ProviderError::Request(
+                "503 Service Unavailable".to_string(),
+            ))
+        }
+
+        async fn symbols(&self) -> ProviderResult<HashMap<String, String>> {
+            Err(ProviderError::Request(
+                "503 Service Unavailable".to_string(),
+            ))
+        }
+
+        async fn historical(
+            &self,
+            base: &str,
+            from: &Date,
+            to: &Date,
+        ) -> ProviderResult<HashMap<Date, ExchangeRate>> {
+            Err(ProviderError::Request(
+                "503 Service Unavailable".to_string(),
+            ))
         }
     }
 
     #[actix_web::test]
     async fn test_rates_of_single_provider() {
         let mut rates = HashMap::new();
-        rates.insert("USD".to_string(), 1.1);
-        rates.insert("GBP".to_string(), 0.85);
+        rates.insert("USD".to_string(), dec!(1.1));
+        rates.insert("GBP".to_string(), dec!(0.85));
         let mock_provider = MockProvider {
             name: "Test Provider".to_string(),
             rates,
         };
         static MOCK_PROVIDERS: OnceLock<Providers> = OnceLock::new();
-        MOCK_PROVIDERS.get_or_init(|| vec![Box::new(mock_provider)]);
+        MOCK_PROVIDERS.get_or_init(|| vec![GuardedProvider::new(Box::new(mock_provider))]);
 
         let result = rates_of_with("EUR", || MOCK_PROVIDERS.get().unwrap()).await;
 
         assert_eq!(result.base, "EUR");
         assert_eq!(result.rates.len(), 2);
-        assert_eq!(result.rates.get("USD"), Some(&1.1));
-        assert_eq!(result.rates.get("GBP"), Some(&0.85));
+        assert_eq!(result.rates.get("USD"), Some(&dec!(1.1)));
+        assert_eq!(result.rates.get("GBP"), Some(&dec!(0.85)));
     }
 
     #[actix_web::test]
     async fn test_rates_of_multiple_providers_with_priority() {
         // Arrange
         let mut primary_rates = HashMap::new();
-        primary_rates.insert("USD".to_string(), 1.1);
-        primary_rates.insert("GBP".to_string(), 0.85);
+        primary_rates.insert("USD".to_string(), dec!(1.1));
+        primary_rates.insert("GBP".to_string(), dec!(0.85));
 
         let mut secondary_rates = HashMap::new();
-        secondary_rates.insert("USD".to_string(), 1.2); // Should be overridden by primary
-        secondary_rates.insert("JPY".to_string(), 130.0); // Should be included
+        secondary_rates.insert("USD".to_string(), dec!(1.2)); // Should be overridden by primary
+        secondary_rates.insert("JPY".to_string(), dec!(130.0)); // Should be included
 
         let primary_provider = MockProvider {
             name: "Primary".to_string(),
@@ -218,16 +726,40 @@ mod tests {
         };
         static MOCK_PROVIDERS: OnceLock<Providers> = OnceLock::new();
         // use the same order as in the real providers
-        MOCK_PROVIDERS
-            .get_or_init(|| vec![Box::new(primary_provider), Box::new(secondary_provider)]);
+        MOCK_PROVIDERS.get_or_init(|| {
+            vec![
+                GuardedProvider::new(Box::new(primary_provider)),
+                GuardedProvider::new(Box::new(secondary_provider)),
+            ]
+        });
 
         let result = rates_of_with("EUR", || MOCK_PROVIDERS.get().unwrap()).await;
 
         assert_eq!(result.base, "EUR");
         assert_eq!(result.rates.len(), 3);
-        assert_eq!(result.rates.get("USD"), Some(&1.1)); // Primary rate
-        assert_eq!(result.rates.get("GBP"), Some(&0.85));
-        assert_eq!(result.rates.get("JPY"), Some(&130.0));
+        assert_eq!(result.rates.get("USD"), Some(&dec!(1.1))); // Primary rate
+        assert_eq!(result.rates.get("GBP"), Some(&dec!(0.85)));
+        assert_eq!(result.rates.get("JPY"), Some(&dec!(130.0)));
+    }
+
+    #[actix_web::test]
+    async fn test_refresh_keeps_the_warm_entry_when_the_providers_fail() {
+        let mut cache = cached::TimedCache::with_lifespan(std::time::Duration::from_secs(3600));
+        let warm = ExchangeRate {
+            base: "EUR".to_string(),
+            overridden: Vec::new(),
+            dates: HashMap::new(),
+            rates: HashMap::from([("USD".to_string(), dec!(1.1))]),
+        };
+        refresh_entry(&mut cache, "EUR".to_string(), warm);
+        static MOCK_PROVIDERS: OnceLock<Providers> = OnceLock::new();
+        MOCK_PROVIDERS.get_or_init(|| vec![GuardedProvider::new(Box::new(FailingProvider {}))]);
+
+        let failed = rates_of_with("EUR", || MOCK_PROVIDERS.get().unwrap()).await;
+        refresh_entry(&mut cache, "EUR".to_string(), failed);
+
+        let entry = cache.cache_get(&"EUR".to_string()).unwrap();
+        assert_eq!(entry.rates.get("USD"), Some(&dec!(1.1)));
     }
 
     #[actix_web::test]
@@ -243,12 +775,12 @@ mod tests {
     #[actix_web::test]
     async fn test_historical_rates_with_multiple_providers_and_priority() {
         let mut primary_rates = HashMap::new();
-        primary_rates.insert("USD".to_string(), 1.1);
-        primary_rates.insert("GBP".to_string(), 0.85);
+        primary_rates.insert("USD".to_string(), dec!(1.1));
+        primary_rates.insert("GBP".to_string(), dec!(0.85));
 
         let mut secondary_rates = HashMap::new();
-        secondary_rates.insert("USD".to_string(), 1.2); // Should be overridden by primary
-        secondary_rates.insert("JPY".to_string(), 130.0); // Should be included
+        secondary_rates.insert("USD".to_string(), dec!(1.2)); // Should be overridden by primary
+        secondary_rates.insert("JPY".to_string(), dec!(130.0)); // Should be included
 
         let primary_provider = MockProvider {
             name: "Primary".to_string(),
@@ -260,8 +792,12 @@ mod tests {
         };
         static MOCK_PROVIDERS: OnceLock<Providers> = OnceLock::new();
         // use the same order as in the real providers
-        MOCK_PROVIDERS
-            .get_or_init(|| vec![Box::new(primary_provider), Box::new(secondary_provider)]);
+        MOCK_PROVIDERS.get_or_init(|| {
+            vec![
+                GuardedProvider::new(Box::new(primary_provider)),
+                GuardedProvider::new(Box::new(secondary_provider)),
+            ]
+        });
 
         let from = Date::from_calendar_date(2024, November, 12).unwrap();
         let to = from.add(Duration::days(3));
@@ -273,23 +809,113 @@ mod tests {
         let day1 = result.get(&from).unwrap();
         assert_eq!(day1.base, "EUR");
         assert_eq!(day1.rates.len(), 3);
-        assert_eq!(day1.rates.get("USD"), Some(&2.1)); // Primary rate
-        assert_eq!(day1.rates.get("GBP"), Some(&1.85));
-        assert_eq!(day1.rates.get("JPY"), Some(&131.0));
+        assert_eq!(day1.rates.get("USD"), Some(&dec!(2.1))); // Primary rate
+        assert_eq!(day1.rates.get("GBP"), Some(&dec!(1.85)));
+        assert_eq!(day1.rates.get("JPY"), Some(&dec!(131.0)));
         let day4 = result.get(&to).unwrap();
         assert_eq!(day4.base, "EUR");
         assert_eq!(day4.rates.len(), 3);
-        assert_eq!(day4.rates.get("USD"), Some(&5.1)); // Primary rate
-        assert_eq!(day4.rates.get("GBP"), Some(&4.85));
-        assert_eq!(day4.rates.get("JPY"), Some(&134.0));
+        assert_eq!(day4.rates.get("USD"), Some(&dec!(5.1))); // Primary rate
+        assert_eq!(day4.rates.get("GBP"), Some(&dec!(4.85)));
+        assert_eq!(day4.rates.get("JPY"), Some(&dec!(134.0)));
+    }
+
+    // serves every day like the overrides
+    struct VirtualProvider {}
+
+    #[allow(unused_variables)]
+    #[async_trait]
+    impl RateProvider for VirtualProvider {
+        fn provider_name(&self) -> &str {
+            "Virtual"
+        }
+
+        fn is_virtual(&self) -> bool {
+            true
+        }
+
+        async fn latest(&self, base: &str) -> ProviderResult<ExchangeRate> {
+            Ok(ExchangeRate::empty(base))
+        }
+
+        async fn symbols(&self) -> ProviderResult<HashMap<String, String>> {
+            Ok(HashMap::new())
+        }
+
+        async fn historical(
+            &self,
+            base: &str,
+            from: &Date,
+            to: &Date,
+        ) -> ProviderResult<HashMap<Date, ExchangeRate>> {
+            let days = to.to_julian_day() - from.to_julian_day();
+            Ok((0..=days)
+                .map(|i| {
+                    let rates = ExchangeRate {
+                        base: base.to_string(),
+                        overridden: vec!["USD".to_string()],
+                        dates: HashMap::new(),
+                        rates: HashMap::from([("USD".to_string(), dec!(1.5))]),
+                    };
+                    (from.add(Duration::days(i as i64)), rates)
+                })
+                .collect())
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_historical_rates_with_virtual_provider() {
+        let from = Date::from_calendar_date(2024, November, 12).unwrap();
+        let primary_provider = MockProvider {
+            name: "Primary".to_string(),
+            rates: HashMap::from([
+                ("USD".to_string(), dec!(1.1)),
+                ("GBP".to_string(), dec!(0.85)),
+            ]),
+        };
+        static MOCK_PROVIDERS: OnceLock<Providers> = OnceLock::new();
+        MOCK_PROVIDERS.get_or_init(|| {
+            vec![
+                GuardedProvider::new(Box::new(VirtualProvider {})),
+                GuardedProvider::new(Box::new(primary_provider)),
+            ]
+        });
+
+        let result =
+            historical_rates_of_with("EUR", from, from, || MOCK_PROVIDERS.get().unwrap()).await;
+
+        assert_eq!(result.len(), 1);
+        let day = result.get(&from).unwrap();
+        assert_eq!(day.rates.get("USD"), Some(&dec!(1.5)));
+        assert_eq!(day.rates.get("GBP"), Some(&dec!(1.85)));
+        assert_eq!(day.overridden, vec!["USD"]);
+    }
+
+    #[actix_web::test]
+    async fn test_historical_rates_without_quotes_of_upstream_providers() {
+        static MOCK_PROVIDERS: OnceLock<Providers> = OnceLock::new();
+        MOCK_PROVIDERS.get_or_init(|| {
+            vec![
+                GuardedProvider::new(Box::new(VirtualProvider {})),
+                GuardedProvider::new(Box::new(FailingProvider {})),
+            ]
+        });
+
+        let from = Date::from_calendar_date(2024, November, 12).unwrap();
+        let to = from.add(Duration::days(3));
+        let result =
+            historical_rates_of_with("EUR", from, to, || MOCK_PROVIDERS.get().unwrap()).await;
+
+        // the virtual provider alone doesn't make a time series
+        assert!(result.is_empty());
     }
 
     #[actix_web::test]
     async fn test_historical_rates_with_empty_multiple_providers() {
         let primary_rates = HashMap::new();
         let mut secondary_rates = HashMap::new();
-        secondary_rates.insert("USD".to_string(), 1.2); // Should be included
-        secondary_rates.insert("JPY".to_string(), 130.0); // Should be included
+        secondary_rates.insert("USD".to_string(), dec!(1.2)); // Should be included
+        secondary_rates.insert("JPY".to_string(), dec!(130.0)); // Should be included
 
         let primary_provider = MockProvider {
             name: "Primary".to_string(),
@@ -301,8 +927,12 @@ mod tests {
         };
         static MOCK_PROVIDERS: OnceLock<Providers> = OnceLock::new();
         // use the same order as in the real providers
-        MOCK_PROVIDERS
-            .get_or_init(|| vec![Box::new(primary_provider), Box::new(secondary_provider)]);
+        MOCK_PROVIDERS.get_or_init(|| {
+            vec![
+                GuardedProvider::new(Box::new(primary_provider)),
+                GuardedProvider::new(Box::new(secondary_provider)),
+            ]
+        });
 
         let from = Date::from_calendar_date(2024, November, 12).unwrap();
         let to = from.add(Duration::days(2));
@@ -314,14 +944,238 @@ mod tests {
         let day1 = result.get(&from).unwrap();
         assert_eq!(day1.base, "EUR");
         assert_eq!(day1.rates.len(), 2);
-        assert_eq!(day1.rates.get("USD"), Some(&2.2)); // Secondary rate
+        assert_eq!(day1.rates.get("USD"), Some(&dec!(2.2))); // Secondary rate
         assert!(!day1.rates.contains_key("GBP"));
-        assert_eq!(day1.rates.get("JPY"), Some(&131.0));
+        assert_eq!(day1.rates.get("JPY"), Some(&dec!(131.0)));
         let day3 = result.get(&to).unwrap();
         assert_eq!(day3.base, "EUR");
         assert_eq!(day3.rates.len(), 2);
-        assert_eq!(day3.rates.get("USD"), Some(&4.2)); // Secondary rate
+        assert_eq!(day3.rates.get("USD"), Some(&dec!(4.2))); // Secondary rate
         assert!(!day3.rates.contains_key("GBP"));
-        assert_eq!(day3.rates.get("JPY"), Some(&133.0));
+        assert_eq!(day3.rates.get("JPY"), Some(&dec!(133.0)));
+    }
+
+    #[actix_web::test]
+    async fn test_rates_of_skips_failing_provider() {
+        let mut rates = HashMap::new();
+        rates.insert("USD".to_string(), dec!(1.1));
+        let mock_provider = MockProvider {
+            name: "Secondary".to_string(),
+            rates,
+        };
+        static MOCK_PROVIDERS: OnceLock<Providers> = OnceLock::new();
+        MOCK_PROVIDERS.get_or_init(|| {
+            vec![
+                GuardedProvider::new(Box::new(FailingProvider {})),
+                GuardedProvider::new(Box::new(mock_provider)),
+            ]
+        });
+
+        let result = rates_of_with("EUR", || MOCK_PROVIDERS.get().unwrap()).await;
+
+        assert_eq!(result.rates.len(), 1);
+        assert_eq!(result.rates.get("USD"), Some(&dec!(1.1)));
+    }
+
+    #[actix_web::test]
+    async fn test_guarded_provider_opens_circuit() {
+        let guarded = GuardedProvider {
+            provider: Box::new(FailingProvider {}),
+            breaker: CircuitBreaker::new(2, std::time::Duration::from_secs(60)),
+            stats: ProviderStats::default(),
+        };
+
+        assert!(guarded.latest("EUR").await.rates.is_empty());
+        assert_eq!(guarded.status().circuit.state, CircuitState::Closed);
+        assert!(guarded.symbols().await.is_empty());
+
+        let status = guarded.status();
+        assert_eq!(status.name, "Failing");
+        assert_eq!(status.circuit.state, CircuitState::Open);
+        assert_eq!(status.circuit.consecutive_failures, 2);
+        assert!(status.last_success.is_none());
+        assert_eq!(
+            status.last_error.unwrap().message,
+            "request failed: 503 Service Unavailable"
+        );
+        assert_eq!(status.latency.samples, 2);
+        // skipped while open, the failure count is not increased
+        guarded.latest("EUR").await;
+        assert_eq!(guarded.status().circuit.consecutive_failures, 2);
+        assert_eq!(guarded.status().latency.samples, 2);
+    }
+
+    #[actix_web::test]
+    async fn test_guarded_provider_records_success() {
+        let mut rates = HashMap::new();
+        rates.insert("USD".to_string(), dec!(1.1));
+        rates.insert("GBP".to_string(), dec!(0.85));
+        let guarded = GuardedProvider::new(Box::new(MockProvider {
+            name: "Test Provider".to_string(),
+            rates,
+        }));
+
+        guarded.latest("EUR").await;
+
+        let status = guarded.status();
+        assert!(status.last_success.is_some());
+        assert!(status.last_error.is_none());
+        assert_eq!(status.currencies, Some(2));
+        assert_eq!(status.latency.samples, 1);
+        assert!(!status.quota.rate_limited);
+    }
+
+    #[test]
+    fn test_provider_error_rate_limited() {
+        let error = ProviderError::RateLimited("429 Too Many Requests".to_string());
+        assert!(error.is_rate_limited());
+        assert_eq!(error.to_string(), "rate limited: 429 Too Many Requests");
+        assert!(!ProviderError::Request("timeout".to_string()).is_rate_limited());
+    }
+
+    #[test]
+    fn test_provider_error_from_status() {
+        let error = |code: u16| {
+            let status = reqwest::StatusCode::from_u16(code).unwrap();
+            ProviderError::from_status(status, code.to_string())
+        };
+
+        assert!(error(429).is_rate_limited());
+        assert!(error(404).is_unsupported());
+        assert!(error(422).is_unsupported());
+        for code in [400, 401, 403, 410, 500, 503] {
+            assert_eq!(error(code), ProviderError::Request(code.to_string()));
+        }
+    }
+
+    // replies 404 for every base like an upstream without KES
+    struct UnsupportedProvider {}
+
+    #[allow(unused_variables)]
+    #[async_trait]
+    impl RateProvider for UnsupportedProvider {
+        fn provider_name(&self) -> &str {
+            "Unsupported"
+        }
+
+        async fn latest(&self, base: &str) -> ProviderResult<ExchangeRate> {
+            Err(ProviderError::Unsupported("404 Not Found".to_string()))
+        }
+
+        async fn symbols(&self) -> ProviderResult<HashMap<String, String>> {
+            Err(ProviderError::Unsupported("404 Not Found".to_string()))
+        }
+
+        async fn historical(
+            &self,
+            base: &str,
+            from: &Date,
+            to: &Date,
+        ) -> ProviderResult<HashMap<Date, ExchangeRate>> {
+            Err(ProviderError::Unsupported("404 Not Found".to_string()))
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_guarded_provider_ignores_unsupported_requests() {
+        let guarded = GuardedProvider {
+            provider: Box::new(UnsupportedProvider {}),
+            breaker: CircuitBreaker::new(2, std::time::Duration::from_secs(60)),
+            stats: ProviderStats::default(),
+        };
+
+        for _ in 0..3 {
+            assert!(guarded.latest("KES").await.rates.is_empty());
+        }
+
+        let status = guarded.status();
+        assert_eq!(status.circuit.state, CircuitState::Closed);
+        assert_eq!(status.circuit.consecutive_failures, 0);
+        assert!(status.last_error.is_none());
+        assert!(status.last_success.is_none());
+    }
+
+    #[test]
+    fn test_derived_rates_of_legacy_currency() {
+        assert_eq!(quoted_currency("DEM"), "EUR");
+        assert_eq!(quoted_currency("CHF"), "CHF");
+        let ghs = ExchangeRate {
+            base: "GHS".to_string(),
+            overridden: Vec::new(),
+            dates: HashMap::new(),
+            rates: HashMap::from([("USD".to_string(), dec!(0.0625))]),
+        };
+        let november = Date::from_calendar_date(2024, November, 12).unwrap();
+
+        let ghc = derived_rates("GHC", ghs.clone(), november).unwrap();
+        assert_eq!(ghc.base, "GHC");
+        assert_eq!(ghc.rates["GHS"], dec!(0.0001));
+        assert_eq!(ghc.rates["USD"], dec!(0.00000625));
+        // the cedi was floating before the redenomination
+        let floating = Date::from_calendar_date(2007, time::Month::June, 29).unwrap();
+        assert!(derived_rates("GHC", ghs, floating).is_none());
+    }
+
+    #[test]
+    fn test_merge_symbols_with_priority() {
+        let names = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(code, name)| (code.to_string(), name.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        let merged = merge_symbols([
+            (
+                "Primary",
+                names(&[("USD", "US Dollar"), ("CHF", "Swiss Franc")]),
+            ),
+            (
+                "Secondary",
+                names(&[
+                    ("usd", "U.S. Dollar"),
+                    ("CHF", "swiss  franc"),
+                    ("KES", "Kenyan Shilling"),
+                ]),
+            ),
+            ("Tertiary", names(&[("USD", "United States Dollar")])),
+        ]);
+
+        assert_eq!(merged.names.len(), 3);
+        assert_eq!(merged.names["USD"], "US Dollar");
+        assert_eq!(merged.names["CHF"], "Swiss Franc");
+        assert_eq!(merged.names["KES"], "Kenyan Shilling");
+        // differing in case and spacing only
+        assert_eq!(merged.conflicts.len(), 1);
+        let usd = &merged.conflicts[0];
+        assert_eq!(usd.code, "USD");
+        assert_eq!(usd.chosen, "US Dollar");
+        assert_eq!(
+            usd.names
+                .iter()
+                .map(|n| (n.provider.as_str(), n.name.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Primary", "US Dollar"),
+                ("Secondary", "U.S. Dollar"),
+                ("Tertiary", "United States Dollar")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_symbols_prefers_the_uppercase_code() {
+        // the order of a map differs from one instance to the other
+        for _ in 0..20 {
+            let names = HashMap::from([
+                ("ugx".to_string(), "Ugandan shilling (old)".to_string()),
+                ("UGX".to_string(), "Ugandan Shilling".to_string()),
+            ]);
+
+            let merged = merge_symbols([("Primary", names)]);
+
+            assert_eq!(merged.names["UGX"], "Ugandan Shilling");
+            assert!(merged.conflicts.is_empty());
+        }
     }
 }
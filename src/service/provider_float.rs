@@ -1,23 +1,25 @@
 use crate::route::model::ExchangeRate;
-use crate::service::provider::RateProvider;
+use crate::service::http::http_client;
+use crate::service::provider::{ProviderError, ProviderResult, RateProvider};
 use async_trait::async_trait;
 use log::info;
-use reqwest::Client;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::LazyLock;
-use time::Date;
+use time::format_description::well_known::Rfc2822;
+use time::{Date, OffsetDateTime};
 
 pub struct FloatRateProvider {}
 
-static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(Client::new);
-
 // internal response
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct FloatRateEntry {
     pub code: String,
     pub name: String,
-    pub rate: f32,
+    pub rate: Decimal,
+    // time of the quote, e.g. Tue, 12 Nov 2024 11:55:01 GMT
+    #[serde(default)]
+    pub date: Option<String>,
 }
 
 impl FloatRateProvider {
@@ -27,27 +29,29 @@ impl FloatRateProvider {
         FloatRateProvider {}
     }
 
-    async fn retrieve(&self, base: &str) -> Vec<FloatRateEntry> {
-        let reply = HTTP_CLIENT
-            .get(format!(
-                "{}/daily/{}.json",
-                FloatRateProvider::HOST,
-                base.to_lowercase()
-            ))
-            .header("User-Agent", "actix-web")
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .unwrap();
-        let reply = reply
-            .json::<HashMap<String, FloatRateEntry>>()
+    async fn retrieve(&self, base: &str) -> ProviderResult<Vec<FloatRateEntry>> {
+        let url = format!(
+            "{}/daily/{}.json",
+            FloatRateProvider::HOST,
+            base.to_lowercase()
+        );
+        let reply = http_client()
+            .get(&url)
             .await
-            .unwrap();
+            .and_then(|reply| reply.error_for_status())?;
+        // failures are logged by the guard of the provider
+        let reply = reply.json::<HashMap<String, FloatRateEntry>>().await?;
         info!("base={:#?}, {:#?} rates", base, reply.len());
-        reply.values().cloned().collect()
+        Ok(reply.into_values().collect())
     }
 }
 
+fn quote_date(date: &str) -> Option<Date> {
+    OffsetDateTime::parse(date, &Rfc2822)
+        .ok()
+        .map(|at| at.date())
+}
+
 #[async_trait]
 impl RateProvider for FloatRateProvider {
     fn provider_name(&self) -> &'static str {
@@ -56,20 +60,26 @@ impl RateProvider for FloatRateProvider {
 
     // latest exchange rate
 
-    async fn latest(&self, base: &str) -> ExchangeRate {
-        let reply = self.retrieve(base).await;
-        ExchangeRate {
+    async fn latest(&self, base: &str) -> ProviderResult<ExchangeRate> {
+        let reply = self.retrieve(base).await?;
+        Ok(ExchangeRate {
             base: base.to_owned(),
+            overridden: Vec::new(),
+            dates: reply
+                .iter()
+                .filter_map(|e| Some((e.code.clone(), quote_date(e.date.as_deref()?)?)))
+                .collect(),
             rates: reply.into_iter().map(|e| (e.code, e.rate)).collect(),
-        }
+        })
     }
 
-    async fn symbols(&self) -> HashMap<String, String> {
-        self.retrieve("CHF")
-            .await
+    async fn symbols(&self) -> ProviderResult<HashMap<String, String>> {
+        Ok(self
+            .retrieve("CHF")
+            .await?
             .into_iter()
             .map(|e| (e.code, e.name))
-            .collect()
+            .collect())
     }
 
     async fn historical(
@@ -77,8 +87,9 @@ impl RateProvider for FloatRateProvider {
         _base: &str,
         _from: &Date,
         _to: &Date,
-    ) -> HashMap<Date, ExchangeRate> {
-        HashMap::new()
+    ) -> ProviderResult<HashMap<Date, ExchangeRate>> {
+        // the daily feed has the latest rates only
+        Err(ProviderError::Unsupported("historical rates".to_string()))
     }
 }
 
@@ -88,7 +99,7 @@ mod tests {
     use time::Month::November;
 
     #[actix_web::test]
-    async fn test_historical_empty_response() {
+    async fn test_historical_unsupported() {
         let provider = FloatRateProvider::new(); // Replace with your actual provider struct
         let base = "USD";
         let from = Date::from_calendar_date(2023, time::Month::January, 1).unwrap();
@@ -96,7 +107,7 @@ mod tests {
 
         let result = provider.historical(base, &from, &to).await;
 
-        assert!(result.is_empty());
+        assert!(result.unwrap_err().is_unsupported());
     }
 
     #[actix_web::test]
@@ -108,6 +119,15 @@ mod tests {
 
         let result = provider.historical(base, &from, &to).await;
 
-        assert!(result.is_empty());
+        assert!(result.unwrap_err().is_unsupported());
+    }
+
+    #[test]
+    fn test_quote_date() {
+        assert_eq!(
+            quote_date("Tue, 12 Nov 2024 11:55:01 GMT"),
+            Some(Date::from_calendar_date(2024, November, 12).unwrap())
+        );
+        assert_eq!(quote_date("yesterday"), None);
     }
 }
@@ -1,25 +1,23 @@
 use crate::route::model::ExchangeRate;
-use crate::service::provider::RateProvider;
+use crate::service::http::http_client;
+use crate::service::provider::{ProviderResult, RateProvider};
 use async_trait::async_trait;
 use log::{error, info};
-use reqwest::Client;
+use rust_decimal::Decimal;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::LazyLock;
 use time::format_description::well_known::Iso8601;
 use time::Date;
 
 pub struct FrankfurterV2RateProvider {}
 
-static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(Client::new);
-
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct FrankfurterV2RateEntry {
     date: String,
     base: String,
     quote: String,
-    rate: f32,
+    rate: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -35,41 +33,31 @@ impl FrankfurterV2RateProvider {
         FrankfurterV2RateProvider {}
     }
 
-    async fn retrieve<T>(&self, path: &str) -> T
+    async fn retrieve<T>(&self, path: &str) -> ProviderResult<T>
     where
-        T: DeserializeOwned + Default,
+        T: DeserializeOwned,
     {
         let url = format!("{}/{}", FrankfurterV2RateProvider::HOST, path);
-        match HTTP_CLIENT
+        let reply = http_client()
             .get(&url)
-            .header("User-Agent", "actix-web")
-            .header("Content-Type", "application/json")
-            .send()
             .await
-        {
-            Ok(reply) => match reply.error_for_status() {
-                Ok(reply) => reply.json::<T>().await.unwrap_or_else(|e| {
-                    error!(
-                        "Failed to parse Frankfurter v2 response from {}: {}",
-                        url, e
-                    );
-                    T::default()
-                }),
-                Err(e) => {
-                    error!("Frankfurter v2 request failed for {}: {}", url, e);
-                    T::default()
-                }
-            },
-            Err(e) => {
-                error!("Frankfurter v2 request failed for {}: {}", url, e);
-                T::default()
-            }
-        }
+            .and_then(|reply| reply.error_for_status())?;
+        // failures are logged by the guard of the provider
+        Ok(reply.json::<T>().await?)
     }
 
     fn rows_to_exchange_rate(base: &str, rows: Vec<FrankfurterV2RateEntry>) -> ExchangeRate {
+        let format = Iso8601::DATE;
         ExchangeRate {
             base: base.to_string(),
+            overridden: Vec::new(),
+            dates: rows
+                .iter()
+                .filter_map(|entry| {
+                    let date = Date::parse(&entry.date, &format).ok()?;
+                    Some((entry.quote.clone(), date))
+                })
+                .collect(),
             rates: rows
                 .into_iter()
                 .map(|entry| (entry.quote, entry.rate))
@@ -109,23 +97,29 @@ impl RateProvider for FrankfurterV2RateProvider {
         "Frankfurter v2"
     }
 
-    async fn latest(&self, base: &str) -> ExchangeRate {
+    async fn latest(&self, base: &str) -> ProviderResult<ExchangeRate> {
         let rows = self
             .retrieve::<Vec<FrankfurterV2RateEntry>>(&format!("rates?base={}", base))
-            .await;
+            .await?;
         info!("base={:#?}, {:#?} Frankfurter v2 rates", base, rows.len());
-        Self::rows_to_exchange_rate(base, rows)
+        Ok(Self::rows_to_exchange_rate(base, rows))
     }
 
-    async fn symbols(&self) -> HashMap<String, String> {
-        self.retrieve::<Vec<FrankfurterV2Currency>>("currencies")
-            .await
+    async fn symbols(&self) -> ProviderResult<HashMap<String, String>> {
+        Ok(self
+            .retrieve::<Vec<FrankfurterV2Currency>>("currencies")
+            .await?
             .into_iter()
             .map(|entry| (entry.iso_code, entry.name))
-            .collect()
+            .collect())
     }
 
-    async fn historical(&self, base: &str, from: &Date, to: &Date) -> HashMap<Date, ExchangeRate> {
+    async fn historical(
+        &self,
+        base: &str,
+        from: &Date,
+        to: &Date,
+    ) -> ProviderResult<HashMap<Date, ExchangeRate>> {
         let format = Iso8601::DATE;
         let iso_from = from.format(&format).unwrap();
         let iso_to = to.format(&format).unwrap();
@@ -134,14 +128,15 @@ impl RateProvider for FrankfurterV2RateProvider {
                 "rates?base={}&from={}&to={}",
                 base, iso_from, iso_to
             ))
-            .await;
-        Self::rows_to_history(base, rows)
+            .await?;
+        Ok(Self::rows_to_history(base, rows))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rust_decimal_macros::dec;
     use time::macros::date;
 
     #[test]
@@ -151,13 +146,13 @@ mod tests {
                 date: "2024-01-01".to_string(),
                 base: "CHF".to_string(),
                 quote: "NPR".to_string(),
-                rate: 158.18,
+                rate: dec!(158.18),
             },
             FrankfurterV2RateEntry {
                 date: "2024-01-01".to_string(),
                 base: "CHF".to_string(),
                 quote: "UGX".to_string(),
-                rate: 4603.61,
+                rate: dec!(4603.61),
             },
         ];
 
@@ -165,8 +160,8 @@ mod tests {
 
         assert_eq!(rates.base, "CHF");
         assert_eq!(rates.rates.len(), 2);
-        assert_eq!(rates.rates.get("NPR"), Some(&158.18));
-        assert_eq!(rates.rates.get("UGX"), Some(&4603.61));
+        assert_eq!(rates.rates.get("NPR"), Some(&dec!(158.18)));
+        assert_eq!(rates.rates.get("UGX"), Some(&dec!(4603.61)));
     }
 
     #[test]
@@ -176,19 +171,19 @@ mod tests {
                 date: "2024-01-01".to_string(),
                 base: "CHF".to_string(),
                 quote: "NPR".to_string(),
-                rate: 158.18,
+                rate: dec!(158.18),
             },
             FrankfurterV2RateEntry {
                 date: "2024-01-01".to_string(),
                 base: "CHF".to_string(),
                 quote: "UGX".to_string(),
-                rate: 4603.61,
+                rate: dec!(4603.61),
             },
             FrankfurterV2RateEntry {
                 date: "2024-01-02".to_string(),
                 base: "CHF".to_string(),
                 quote: "NPR".to_string(),
-                rate: 157.76,
+                rate: dec!(157.76),
             },
         ];
 
@@ -202,7 +197,7 @@ mod tests {
                 .unwrap()
                 .rates
                 .get("NPR"),
-            Some(&158.18)
+            Some(&dec!(158.18))
         );
         assert_eq!(
             history
@@ -210,7 +205,7 @@ mod tests {
                 .unwrap()
                 .rates
                 .get("UGX"),
-            Some(&4603.61)
+            Some(&dec!(4603.61))
         );
         assert_eq!(
             history
@@ -218,7 +213,24 @@ mod tests {
                 .unwrap()
                 .rates
                 .get("NPR"),
-            Some(&157.76)
+            Some(&dec!(157.76))
         );
     }
+
+    #[test]
+    fn test_keeps_the_precision_of_the_reply() {
+        let entry: FrankfurterV2RateEntry = serde_json::from_str(
+            r#"{"date": "2024-01-01", "base": "CHF", "quote": "UGX", "rate": 4603.6123456789}"#,
+        )
+        .unwrap();
+
+        assert_eq!(entry.rate, dec!(4603.6123456789));
+        // more digits than a double holds
+        let entry: FrankfurterV2RateEntry = serde_json::from_str(
+            r#"{"date": "2024-01-01", "base": "VND", "quote": "CHF", "rate": 0.0000123456789012345678}"#,
+        )
+        .unwrap();
+
+        assert_eq!(entry.rate, dec!(0.0000123456789012345678));
+    }
 }
@@ -1,25 +1,24 @@
 use crate::route::model::ExchangeRate;
-use crate::service::provider::RateProvider;
+use crate::service::http::http_client;
+use crate::service::provider::{ProviderError, ProviderResult, RateProvider};
 use async_trait::async_trait;
 use futures::{stream, StreamExt};
-use reqwest::{Client, Response};
+use reqwest::Response;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::LazyLock;
 use time::format_description::well_known::Iso8601;
 use time::Date;
 
 pub struct FreeRateProvider {}
 
-static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(Client::new);
-
 // internal response
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct FreeRateEntry {
     date: String,
     // Map to handle dynamic top-level currency keys
     #[serde(flatten)]
-    currencies: HashMap<String, HashMap<String, f32>>,
+    currencies: HashMap<String, HashMap<String, Decimal>>,
 }
 
 impl FreeRateProvider {
@@ -30,42 +29,36 @@ impl FreeRateProvider {
         FreeRateProvider {}
     }
 
-    async fn retrieve(&self, path: &str) -> Response {
-        HTTP_CLIENT
-            .get(format!("{}@{}", FreeRateProvider::HOST, path))
-            .header("User-Agent", "actix-web")
-            .header("Content-Type", "application/json")
-            .send()
+    async fn retrieve(&self, path: &str) -> reqwest::Result<Response> {
+        http_client()
+            .get(&format!("{}@{}", FreeRateProvider::HOST, path))
             .await
-            .unwrap()
+            .and_then(|reply| reply.error_for_status())
     }
 
-    async fn rates_from(&self, base: &str, at: &Date) -> ExchangeRate {
+    async fn rates_from(&self, base: &str, at: &Date) -> ProviderResult<ExchangeRate> {
         let format = Iso8601::DATE;
         let iso_at = at.format(&format).unwrap();
         let key = base.to_lowercase();
         let reply = self
             .retrieve(&format!("{}/v1/currencies/{}.json", iso_at, key))
-            .await;
+            .await?;
         // get JSON hashmap, where the name is variable
-        let base_rate: FreeRateEntry = reply.json::<FreeRateEntry>().await.unwrap_or_else(|e| {
-            log::error!("Failed to parse FreeRateEntry: {}", e);
-            FreeRateEntry {
-                date: String::new(),
-                currencies: HashMap::new(),
-            }
-        });
+        let base_rate: FreeRateEntry = reply.json::<FreeRateEntry>().await?;
         let empty_rates = HashMap::new();
-        let rates: &HashMap<String, f32> = base_rate.currencies.get(&key).unwrap_or(&empty_rates);
-        ExchangeRate {
+        let rates: &HashMap<String, Decimal> =
+            base_rate.currencies.get(&key).unwrap_or(&empty_rates);
+        Ok(ExchangeRate {
             base: base.to_string(),
+            overridden: Vec::new(),
+            dates: HashMap::new(),
             // keep KES and BDT
             rates: rates
                 .iter()
                 .filter(|(k, _v)| k == &"kes" || k == &"bdt")
                 .map(|(k, v)| (k.to_uppercase(), *v))
                 .collect(),
-        }
+        })
     }
 
     async fn rates_between(
@@ -73,7 +66,7 @@ impl FreeRateProvider {
         base: &str,
         from: &Date,
         to: &Date,
-    ) -> HashMap<Date, ExchangeRate> {
+    ) -> ProviderResult<HashMap<Date, ExchangeRate>> {
         // create a vec of dates from to
         let mut dates = Vec::new();
         let mut current = *from;
@@ -82,14 +75,35 @@ impl FreeRateProvider {
             current = current.next_day().unwrap();
         }
 
-        stream::iter(dates)
+        let replies: Vec<(Date, ProviderResult<ExchangeRate>)> = stream::iter(dates)
             .map(|day| async move {
                 let rate = self.rates_from(base, &day).await;
                 (day, rate)
             })
             .buffer_unordered(10) // Process up to 10 requests concurrently
             .collect()
-            .await
+            .await;
+        Self::merge_days(replies)
+    }
+
+    // missing days are tolerated (e.g. today is not published yet), fails only when every day failed
+    fn merge_days(
+        replies: Vec<(Date, ProviderResult<ExchangeRate>)>,
+    ) -> ProviderResult<HashMap<Date, ExchangeRate>> {
+        let mut history = HashMap::new();
+        let mut last_error = None;
+        for (day, reply) in replies {
+            match reply {
+                Ok(rate) => {
+                    history.insert(day, rate);
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        match last_error {
+            Some(e) if history.is_empty() => Err(e),
+            _ => Ok(history),
+        }
     }
 }
 
@@ -99,15 +113,63 @@ impl RateProvider for FreeRateProvider {
         "Free Exchange API"
     }
 
-    async fn latest(&self, base: &str) -> ExchangeRate {
-        ExchangeRate::empty(base)
+    // used for the history only, not reported as a successful call of the provider
+    async fn latest(&self, _base: &str) -> ProviderResult<ExchangeRate> {
+        Err(ProviderError::Unsupported("latest rates".to_string()))
     }
 
-    async fn symbols(&self) -> HashMap<String, String> {
-        HashMap::new()
+    async fn symbols(&self) -> ProviderResult<HashMap<String, String>> {
+        Err(ProviderError::Unsupported("symbols".to_string()))
     }
 
-    async fn historical(&self, base: &str, from: &Date, to: &Date) -> HashMap<Date, ExchangeRate> {
+    async fn historical(
+        &self,
+        base: &str,
+        from: &Date,
+        to: &Date,
+    ) -> ProviderResult<HashMap<Date, ExchangeRate>> {
         self.rates_between(base, from, to).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::date;
+
+    #[test]
+    fn test_merge_days_tolerates_missing_days() {
+        let replies = vec![
+            (date!(2024 - 11 - 11), Ok(ExchangeRate::empty("CHF"))),
+            (
+                date!(2024 - 11 - 12),
+                Err(ProviderError::Request("404 Not Found".to_string())),
+            ),
+        ];
+
+        let history = FreeRateProvider::merge_days(replies).unwrap();
+
+        assert_eq!(history.len(), 1);
+        assert!(history.contains_key(&date!(2024 - 11 - 11)));
+    }
+
+    #[test]
+    fn test_merge_days_fails_when_all_days_failed() {
+        let replies = vec![(
+            date!(2024 - 11 - 12),
+            Err(ProviderError::Request("404 Not Found".to_string())),
+        )];
+
+        let result = FreeRateProvider::merge_days(replies);
+
+        assert_eq!(
+            result.unwrap_err(),
+            ProviderError::Request("404 Not Found".to_string())
+        );
+    }
+
+    #[test]
+    fn test_merge_days_empty_range() {
+        assert!(FreeRateProvider::merge_days(Vec::new()).unwrap().is_empty());
+    }
+}
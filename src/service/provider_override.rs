@@ -0,0 +1,312 @@
+use crate::route::model::{ExchangeRate, NewRateOverride, OverrideKind, RateOverride};
+use crate::service::provider::{ProviderResult, RateProvider};
+use async_trait::async_trait;
+use log::warn;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::env;
+use std::str::FromStr;
+use std::sync::{LazyLock, Mutex, RwLock};
+use time::{Date, Duration, OffsetDateTime};
+
+// pegs and fixed rates, kept in memory, the fixed rates are lost on restart
+struct Overrides {
+    next_id: u64,
+    entries: Vec<RateOverride>,
+}
+
+static OVERRIDES: LazyLock<RwLock<Overrides>> = LazyLock::new(|| {
+    let entries = parse_pegs(env::var("PEGS").ok().as_deref());
+    RwLock::new(Overrides {
+        next_id: entries.len() as u64 + 1,
+        entries,
+    })
+});
+
+// day of the last rates served, the cached rates are stale when an override started or ended since
+static SERVED_ON: Mutex<Option<Date>> = Mutex::new(None);
+
+// virtual provider with the highest priority, serves the pegs and the fixed rates in both directions
+pub struct OverrideRateProvider {}
+
+impl OverrideRateProvider {
+    pub fn new() -> Self {
+        OverrideRateProvider {}
+    }
+}
+
+#[async_trait]
+impl RateProvider for OverrideRateProvider {
+    fn provider_name(&self) -> &str {
+        "Overrides"
+    }
+
+    fn is_virtual(&self) -> bool {
+        true
+    }
+
+    async fn latest(&self, base: &str) -> ProviderResult<ExchangeRate> {
+        let today = OffsetDateTime::now_utc().date();
+        Ok(rates_at(&overrides(), base, today))
+    }
+
+    async fn symbols(&self) -> ProviderResult<HashMap<String, String>> {
+        Ok(HashMap::new())
+    }
+
+    async fn historical(
+        &self,
+        base: &str,
+        from: &Date,
+        to: &Date,
+    ) -> ProviderResult<HashMap<Date, ExchangeRate>> {
+        Ok(history_of(&overrides(), base, *from, *to))
+    }
+}
+
+pub fn overrides() -> Vec<RateOverride> {
+    OVERRIDES.read().unwrap().entries.clone()
+}
+
+// the input is validated by the caller
+pub fn add_override(new: NewRateOverride) -> RateOverride {
+    let mut overrides = OVERRIDES.write().unwrap();
+    let added = RateOverride {
+        id: overrides.next_id,
+        kind: OverrideKind::Fixed,
+        base: new.base.to_uppercase(),
+        counter: new.counter.to_uppercase(),
+        rate: new.rate,
+        valid_from: new.valid_from,
+        valid_to: new.valid_to,
+    };
+    overrides.next_id += 1;
+    overrides.entries.push(added.clone());
+    added
+}
+
+// the pegs are kept, false when there is no fixed rate with the id
+pub fn remove_override(id: u64) -> bool {
+    let mut overrides = OVERRIDES.write().unwrap();
+    let before = overrides.entries.len();
+    overrides
+        .entries
+        .retain(|o| o.id != id || o.kind == OverrideKind::Peg);
+    overrides.entries.len() < before
+}
+
+// true on the first call of a day starting or ending the validity of an override
+pub fn crossed_validity(today: Date) -> bool {
+    let previous = SERVED_ON.lock().unwrap().replace(today);
+    previous.is_some_and(|day| day != today && validity_changed(&overrides(), day, today))
+}
+
+fn validity_changed(entries: &[RateOverride], before: Date, after: Date) -> bool {
+    entries
+        .iter()
+        .any(|o| is_valid_at(o, before) != is_valid_at(o, after))
+}
+
+// rates of the entries valid at the date, the later entries win for the same pair,
+// the inverse rates keep all the digits, rounding would wipe out the small ones
+fn rates_at(entries: &[RateOverride], base: &str, date: Date) -> ExchangeRate {
+    let base = base.to_uppercase();
+    let mut rates = HashMap::new();
+    for entry in entries.iter().filter(|o| is_valid_at(o, date)) {
+        if entry.base == base {
+            rates.insert(entry.counter.clone(), entry.rate);
+        } else if entry.counter == base {
+            let inverse = Decimal::ONE / entry.rate;
+            rates.insert(entry.base.clone(), inverse);
+        }
+    }
+    let mut overridden: Vec<String> = rates.keys().cloned().collect();
+    overridden.sort();
+    let dates = rates
+        .keys()
+        .map(|counter| (counter.clone(), date))
+        .collect();
+    ExchangeRate {
+        base,
+        rates,
+        overridden,
+        dates,
+    }
+}
+
+// days without any entry are left out
+fn history_of(
+    entries: &[RateOverride],
+    base: &str,
+    from: Date,
+    to: Date,
+) -> HashMap<Date, ExchangeRate> {
+    let mut history = HashMap::new();
+    let mut date = from;
+    while date <= to {
+        let rates = rates_at(entries, base, date);
+        if !rates.rates.is_empty() {
+            history.insert(date, rates);
+        }
+        date += Duration::days(1);
+    }
+    history
+}
+
+fn is_valid_at(entry: &RateOverride, date: Date) -> bool {
+    entry.valid_from.is_none_or(|from| from <= date) && entry.valid_to.is_none_or(|to| date <= to)
+}
+
+// given as USD/AED=3.6725,EUR/XOF=655.957 (1 base = rate counter), invalid entries are skipped
+fn parse_pegs(pegs: Option<&str>) -> Vec<RateOverride> {
+    pegs.unwrap_or_default()
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .filter_map(|entry| {
+            let peg = entry.split_once('=').and_then(|(pair, rate)| {
+                let (base, counter) = pair.trim().split_once('/')?;
+                let rate = Decimal::from_str(rate.trim()).ok()?;
+                let valid =
+                    |code: &str| code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic());
+                (valid(base) && valid(counter) && base != counter && rate > Decimal::ZERO)
+                    .then(|| (base.to_uppercase(), counter.to_uppercase(), rate))
+            });
+            if peg.is_none() {
+                warn!(
+                    "PEGS: invalid entry '{}', expected BASE/COUNTER=rate",
+                    entry
+                );
+            }
+            peg
+        })
+        .enumerate()
+        .map(|(i, (base, counter, rate))| RateOverride {
+            id: i as u64 + 1,
+            kind: OverrideKind::Peg,
+            base,
+            counter,
+            rate,
+            valid_from: None,
+            valid_to: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use time::macros::date;
+
+    fn fixed(id: u64, base: &str, counter: &str, rate: Decimal) -> RateOverride {
+        RateOverride {
+            id,
+            kind: OverrideKind::Fixed,
+            base: base.to_string(),
+            counter: counter.to_string(),
+            rate,
+            valid_from: Some(date!(2024 - 11 - 01)),
+            valid_to: Some(date!(2024 - 11 - 30)),
+        }
+    }
+
+    #[test]
+    fn test_parse_pegs() {
+        let pegs = parse_pegs(Some(
+            "usd/aed=3.6725, EUR/XOF=655.957,USD/USD=1,XX/AED=1,USD/AED=-1",
+        ));
+
+        assert_eq!(pegs.len(), 2);
+        assert_eq!(pegs[0].id, 1);
+        assert_eq!(pegs[0].kind, OverrideKind::Peg);
+        assert_eq!(
+            (pegs[0].base.as_str(), pegs[0].counter.as_str()),
+            ("USD", "AED")
+        );
+        assert_eq!(pegs[1].rate, dec!(655.957));
+        assert!(parse_pegs(None).is_empty());
+    }
+
+    #[test]
+    fn test_rates_at() {
+        let entries = parse_pegs(Some("USD/AED=3.6725"))
+            .into_iter()
+            .chain([fixed(2, "CHF", "KES", dec!(146.2))])
+            .collect::<Vec<_>>();
+
+        let usd = rates_at(&entries, "usd", date!(2024 - 11 - 12));
+        assert_eq!(usd.base, "USD");
+        assert_eq!(
+            usd.rates,
+            HashMap::from([("AED".to_string(), dec!(3.6725))])
+        );
+        assert_eq!(usd.overridden, vec!["AED"]);
+        let aed = rates_at(&entries, "AED", date!(2024 - 11 - 12));
+        assert_eq!(aed.rates["USD"], dec!(0.2722940776038121170864533696));
+        let kes = rates_at(&entries, "KES", date!(2024 - 11 - 12));
+        assert_eq!(kes.rates["CHF"], dec!(0.0068399452804377564979480164));
+        // small rates keep their significant digits
+        let pegs = parse_pegs(Some("USD/VND=25400"));
+        let vnd = rates_at(&pegs, "VND", date!(2024 - 11 - 12));
+        assert_eq!(vnd.rates["USD"], dec!(0.0000393700787401574803149606));
+        // out of the validity of the fixed rate
+        assert!(rates_at(&entries, "CHF", date!(2024 - 12 - 01))
+            .rates
+            .is_empty());
+    }
+
+    #[test]
+    fn test_rates_at_later_entries_win() {
+        let entries = parse_pegs(Some("USD/AED=3.6725"))
+            .into_iter()
+            .chain([fixed(2, "AED", "USD", dec!(0.25))])
+            .collect::<Vec<_>>();
+
+        let usd = rates_at(&entries, "USD", date!(2024 - 11 - 12));
+        assert_eq!(usd.rates["AED"], dec!(4));
+        let usd = rates_at(&entries, "USD", date!(2024 - 10 - 31));
+        assert_eq!(usd.rates["AED"], dec!(3.6725));
+    }
+
+    #[test]
+    fn test_validity_changed() {
+        let entries = vec![fixed(1, "CHF", "KES", dec!(146.2))];
+
+        assert!(validity_changed(
+            &entries,
+            date!(2024 - 10 - 31),
+            date!(2024 - 11 - 01)
+        ));
+        assert!(!validity_changed(
+            &entries,
+            date!(2024 - 11 - 01),
+            date!(2024 - 11 - 30)
+        ));
+        assert!(validity_changed(
+            &entries,
+            date!(2024 - 11 - 30),
+            date!(2024 - 12 - 01)
+        ));
+        // not valid on either day
+        assert!(!validity_changed(
+            &entries,
+            date!(2024 - 10 - 01),
+            date!(2024 - 12 - 01)
+        ));
+    }
+
+    #[test]
+    fn test_history_of() {
+        let entries = vec![fixed(1, "CHF", "KES", dec!(146.2))];
+
+        let history = history_of(
+            &entries,
+            "CHF",
+            date!(2024 - 11 - 29),
+            date!(2024 - 12 - 02),
+        );
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[&date!(2024 - 11 - 30)].rates["KES"], dec!(146.2));
+        assert!(!history.contains_key(&date!(2024 - 12 - 01)));
+    }
+}
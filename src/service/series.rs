@@ -0,0 +1,712 @@
+use crate::route::model::{
+    Aggregation, ExchangeRate, Fill, FilledRate, Fluctuation, FluctuationSort, Interval,
+    Observation, Ohlc, PeriodRate, PeriodRates, RateStatistics,
+};
+use rust_decimal::{Decimal, MathematicalOps};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use time::{Date, Duration};
+
+// calculations over the historical rates, the time series are ordered by date
+
+// decimal places of the calculated values (means, interpolations, percentages),
+// the rates delivered by the providers are kept as they are
+const CALCULATED_DP: u32 = 10;
+
+// daily rates of a single counter currency, days without a rate for the counter are skipped
+pub fn counter_series(
+    historical: &HashMap<Date, ExchangeRate>,
+    counter: &str,
+) -> BTreeMap<Date, Decimal> {
+    historical
+        .iter()
+        .flat_map(|(date, ex)| ex.rates.get(counter).map(|rate| (*date, *rate)))
+        .collect()
+}
+
+// first days of the periods with a rate of the counter pegged or overridden by an administrator,
+// the days themselves with the daily interval
+pub fn overridden_periods(
+    historical: &HashMap<Date, ExchangeRate>,
+    counter: &str,
+    interval: Interval,
+) -> BTreeSet<Date> {
+    historical
+        .iter()
+        .filter(|(_, ex)| ex.overridden.iter().any(|c| c == counter))
+        .map(|(date, _)| period_start(*date, interval))
+        .collect()
+}
+
+// counters present on any day of the historical rates, ordered
+fn counters(historical: &HashMap<Date, ExchangeRate>) -> BTreeSet<&String> {
+    historical.values().flat_map(|ex| ex.rates.keys()).collect()
+}
+
+// rate of a day in the time series, filled when it was not observed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub rate: Decimal,
+    pub filled: bool,
+}
+
+// daily calendar from the first to the last day (inclusive), the days without rates are filled,
+// days before the first observation (and after the last one when interpolating) can't be filled
+pub fn fill_gaps(
+    series: &BTreeMap<Date, Decimal>,
+    from: Date,
+    to: Date,
+    fill: Fill,
+) -> BTreeMap<Date, Point> {
+    let observed = |rate: &Decimal| Point {
+        rate: *rate,
+        filled: false,
+    };
+    if fill == Fill::None {
+        return series
+            .iter()
+            .map(|(date, rate)| (*date, observed(rate)))
+            .collect();
+    }
+    let mut points = BTreeMap::new();
+    let mut day = from;
+    while day <= to {
+        let point = match series.get(&day) {
+            Some(rate) => Some(observed(rate)),
+            None => filled_rate(series, day, fill).map(|rate| Point { rate, filled: true }),
+        };
+        if let Some(point) = point {
+            points.insert(day, point);
+        }
+        day = match day.next_day() {
+            Some(next) => next,
+            None => break,
+        };
+    }
+    points
+}
+
+fn filled_rate(series: &BTreeMap<Date, Decimal>, day: Date, fill: Fill) -> Option<Decimal> {
+    let (before, previous) = series.range(..day).next_back()?;
+    match fill {
+        Fill::None => None,
+        Fill::Previous => Some(*previous),
+        Fill::Linear => {
+            let (after, next) = series.range(day..).next()?;
+            let elapsed = Decimal::from((day - *before).whole_days());
+            let span = Decimal::from((*after - *before).whole_days());
+            Some((previous + (next - previous) * elapsed / span).round_dp(CALCULATED_DP))
+        }
+    }
+}
+
+// first day of the period the date belongs to
+fn period_start(date: Date, interval: Interval) -> Date {
+    match interval {
+        Interval::Day => date,
+        Interval::Week => date - Duration::days(date.weekday().number_days_from_monday() as i64),
+        Interval::Month => date.replace_day(1).unwrap_or(date),
+    }
+}
+
+// one rate per period, keyed by the first day of the period
+pub fn resample(
+    series: &BTreeMap<Date, Point>,
+    interval: Interval,
+    aggregation: Aggregation,
+) -> BTreeMap<Date, FilledRate> {
+    let mut periods = BTreeMap::<Date, Vec<Point>>::new();
+    for (date, point) in series {
+        periods
+            .entry(period_start(*date, interval))
+            .or_default()
+            .push(*point);
+    }
+    periods
+        .into_iter()
+        .flat_map(|(start, points)| {
+            let rates = points.iter().map(|p| p.rate).collect::<Vec<_>>();
+            aggregate(&rates, aggregation).map(|rate| {
+                let filled = points.iter().any(|p| p.filled);
+                (start, FilledRate { rate, filled })
+            })
+        })
+        .collect()
+}
+
+// the rates of the period are in date order
+fn aggregate(rates: &[Decimal], aggregation: Aggregation) -> Option<PeriodRate> {
+    let open = *rates.first()?;
+    let close = *rates.last()?;
+    let rate = match aggregation {
+        Aggregation::Last => PeriodRate::Rate(close),
+        Aggregation::Mean => PeriodRate::Rate(
+            (rates.iter().sum::<Decimal>() / Decimal::from(rates.len())).round_dp(CALCULATED_DP),
+        ),
+        Aggregation::Ohlc => PeriodRate::Ohlc(Ohlc {
+            open,
+            high: *rates.iter().max()?,
+            low: *rates.iter().min()?,
+            close,
+        }),
+    };
+    Some(rate)
+}
+
+// change relative to the start in percent, none (zero) for a zero start
+fn percent(change: Decimal, start: Decimal) -> Decimal {
+    (change * Decimal::ONE_HUNDRED)
+        .checked_div(start)
+        .unwrap_or_default()
+        .round_dp(CALCULATED_DP)
+}
+
+// gap fills and resamples every counter of the historical rates between the first and last day
+pub fn resample_all(
+    base: &str,
+    historical: &HashMap<Date, ExchangeRate>,
+    (from, to): (Date, Date),
+    fill: Fill,
+    interval: Interval,
+    aggregation: Aggregation,
+) -> BTreeMap<Date, PeriodRates> {
+    let mut periods = BTreeMap::<Date, PeriodRates>::new();
+    for counter in counters(historical) {
+        let series = fill_gaps(&counter_series(historical, counter), from, to, fill);
+        let overridden = overridden_periods(historical, counter, interval);
+        for (start, rate) in resample(&series, interval, aggregation) {
+            let period = periods.entry(start).or_insert_with(|| PeriodRates {
+                base: base.to_string(),
+                rates: BTreeMap::new(),
+                filled: Vec::new(),
+                overridden: Vec::new(),
+            });
+            period.rates.insert(counter.clone(), rate.rate);
+            if rate.filled {
+                period.filled.push(counter.clone());
+            }
+            if overridden.contains(&start) {
+                period.overridden.push(counter.clone());
+            }
+        }
+    }
+    periods
+}
+
+// none for an empty time series, the overridden days are flagged in the observations
+pub fn statistics(
+    base: &str,
+    counter: &str,
+    series: &BTreeMap<Date, Decimal>,
+    overridden: &BTreeSet<Date>,
+) -> Option<RateStatistics> {
+    let observations = series
+        .iter()
+        .map(|(date, rate)| Observation {
+            date: *date,
+            rate: *rate,
+            overridden: overridden.contains(date),
+        })
+        .collect::<Vec<_>>();
+    let first = observations.first()?.clone();
+    let last = observations.last()?.clone();
+    // the earliest day wins on ties
+    let min = observations
+        .iter()
+        .reduce(|min, o| if o.rate < min.rate { o } else { min })?
+        .clone();
+    let max = observations
+        .iter()
+        .reduce(|max, o| if o.rate > max.rate { o } else { max })?
+        .clone();
+
+    let count = Decimal::from(observations.len());
+    let mean = observations.iter().map(|o| o.rate).sum::<Decimal>() / count;
+    let variance = observations
+        .iter()
+        .map(|o| (o.rate - mean).powi(2))
+        .sum::<Decimal>()
+        / count;
+    let change = last.rate - first.rate;
+
+    Some(RateStatistics {
+        base: base.to_string(),
+        counter: counter.to_string(),
+        observations: observations.len(),
+        mean: mean.round_dp(CALCULATED_DP),
+        stddev: variance.sqrt().unwrap_or_default().round_dp(CALCULATED_DP),
+        change,
+        change_pct: percent(change, first.rate),
+        first,
+        last,
+        min,
+        max,
+    })
+}
+
+// change between the first and last observation of every counter in the historical rates
+pub fn fluctuations(
+    historical: &HashMap<Date, ExchangeRate>,
+    sort: FluctuationSort,
+) -> Vec<Fluctuation> {
+    let mut fluctuations = counters(historical)
+        .into_iter()
+        .flat_map(|counter| {
+            fluctuation(
+                counter,
+                &counter_series(historical, counter),
+                &overridden_periods(historical, counter, Interval::Day),
+            )
+        })
+        .collect::<Vec<_>>();
+    match sort {
+        // already ordered by the counters
+        FluctuationSort::Counter => {}
+        FluctuationSort::Change => fluctuations.sort_by_key(|f| Reverse(f.change.abs())),
+        FluctuationSort::ChangePct => fluctuations.sort_by_key(|f| Reverse(f.change_pct.abs())),
+    }
+    fluctuations
+}
+
+fn fluctuation(
+    counter: &str,
+    series: &BTreeMap<Date, Decimal>,
+    overridden: &BTreeSet<Date>,
+) -> Option<Fluctuation> {
+    let (start_date, start_rate) = series.first_key_value()?;
+    let (end_date, end_rate) = series.last_key_value()?;
+    let change = end_rate - start_rate;
+    Some(Fluctuation {
+        counter: counter.to_string(),
+        start: Observation {
+            date: *start_date,
+            rate: *start_rate,
+            overridden: overridden.contains(start_date),
+        },
+        end: Observation {
+            date: *end_date,
+            rate: *end_rate,
+            overridden: overridden.contains(end_date),
+        },
+        change,
+        change_pct: percent(change, *start_rate),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use time::macros::date;
+
+    #[test]
+    fn test_counter_series() {
+        let historical = HashMap::from([
+            (
+                date!(2024 - 11 - 11),
+                ExchangeRate {
+                    base: "CHF".to_string(),
+                    overridden: Vec::new(),
+                    dates: HashMap::new(),
+                    rates: HashMap::from([
+                        ("EUR".to_string(), dec!(1.05)),
+                        ("USD".to_string(), dec!(1.1)),
+                    ]),
+                },
+            ),
+            (
+                date!(2024 - 11 - 12),
+                ExchangeRate {
+                    base: "CHF".to_string(),
+                    overridden: Vec::new(),
+                    dates: HashMap::new(),
+                    rates: HashMap::from([("USD".to_string(), dec!(1.2))]),
+                },
+            ),
+        ]);
+
+        let series = counter_series(&historical, "USD");
+        assert_eq!(
+            series.into_iter().collect::<Vec<_>>(),
+            vec![
+                (date!(2024 - 11 - 11), dec!(1.1)),
+                (date!(2024 - 11 - 12), dec!(1.2))
+            ]
+        );
+        assert_eq!(counter_series(&historical, "EUR").len(), 1);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let series = BTreeMap::from([
+            (date!(2024 - 11 - 10), dec!(2.0)),
+            (date!(2024 - 11 - 11), dec!(4.0)),
+            (date!(2024 - 11 - 12), dec!(4.0)),
+            (date!(2024 - 11 - 13), dec!(1.0)),
+            (date!(2024 - 11 - 14), dec!(5.0)),
+            (date!(2024 - 11 - 15), dec!(5.0)),
+            (date!(2024 - 11 - 16), dec!(7.0)),
+            (date!(2024 - 11 - 17), dec!(4.0)),
+        ]);
+
+        let stats = statistics("CHF", "EUR", &series, &BTreeSet::new()).unwrap();
+
+        assert_eq!(stats.base, "CHF");
+        assert_eq!(stats.counter, "EUR");
+        assert_eq!(stats.observations, 8);
+        assert_eq!(
+            stats.first,
+            Observation {
+                date: date!(2024 - 11 - 10),
+                rate: dec!(2.0),
+                overridden: false,
+            }
+        );
+        assert_eq!(stats.last.date, date!(2024 - 11 - 17));
+        assert_eq!(stats.min.date, date!(2024 - 11 - 13));
+        assert_eq!(stats.min.rate, dec!(1.0));
+        assert_eq!(stats.max.date, date!(2024 - 11 - 16));
+        assert_eq!(stats.max.rate, dec!(7.0));
+        assert_eq!(stats.mean, dec!(4.0));
+        assert_eq!(stats.stddev, dec!(1.7320508076));
+        assert_eq!(stats.change, dec!(2.0));
+        assert_eq!(stats.change_pct, dec!(100.0));
+    }
+
+    #[test]
+    fn test_statistics_single_observation() {
+        let series = BTreeMap::from([(date!(2024 - 11 - 12), dec!(1.5))]);
+
+        let stats = statistics("CHF", "EUR", &series, &BTreeSet::new()).unwrap();
+
+        assert_eq!(stats.observations, 1);
+        assert_eq!(stats.first, stats.last);
+        assert_eq!(stats.stddev, dec!(0.0));
+        assert_eq!(stats.change_pct, dec!(0.0));
+    }
+
+    #[test]
+    fn test_statistics_without_observations() {
+        assert!(statistics("CHF", "EUR", &BTreeMap::new(), &BTreeSet::new()).is_none());
+    }
+
+    fn historical() -> HashMap<Date, ExchangeRate> {
+        let day = |rates: &[(&str, Decimal)]| ExchangeRate {
+            base: "CHF".to_string(),
+            overridden: Vec::new(),
+            dates: HashMap::new(),
+            rates: rates.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+        };
+        HashMap::from([
+            (
+                date!(2024 - 11 - 10),
+                day(&[("EUR", dec!(1.0)), ("JPY", dec!(170.0)), ("USD", dec!(1.1))]),
+            ),
+            (
+                date!(2024 - 11 - 11),
+                day(&[("EUR", dec!(1.02)), ("KES", dec!(150.0))]),
+            ),
+            (
+                date!(2024 - 11 - 12),
+                day(&[
+                    ("EUR", dec!(1.05)),
+                    ("JPY", dec!(175.1)),
+                    ("USD", dec!(1.0)),
+                ]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_fluctuations() {
+        let fluctuations = fluctuations(&historical(), FluctuationSort::Counter);
+
+        let counters = fluctuations
+            .iter()
+            .map(|f| f.counter.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(counters, vec!["EUR", "JPY", "KES", "USD"]);
+        let eur = &fluctuations[0];
+        assert_eq!(eur.start.date, date!(2024 - 11 - 10));
+        assert_eq!(eur.end.date, date!(2024 - 11 - 12));
+        assert_eq!(eur.change, dec!(0.05));
+        assert_eq!(eur.change_pct, dec!(5.0));
+        // a single observation
+        let kes = &fluctuations[2];
+        assert_eq!(kes.start, kes.end);
+        assert_eq!(kes.change, dec!(0.0));
+    }
+
+    #[test]
+    fn test_fluctuations_sorted_by_change() {
+        let counters = |sort| {
+            fluctuations(&historical(), sort)
+                .into_iter()
+                .map(|f| f.counter)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            counters(FluctuationSort::Change),
+            vec!["JPY", "USD", "EUR", "KES"]
+        );
+        assert_eq!(
+            counters(FluctuationSort::ChangePct),
+            vec!["USD", "EUR", "JPY", "KES"]
+        );
+    }
+
+    #[test]
+    fn test_period_start() {
+        // Wednesday
+        let date = date!(2024 - 11 - 13);
+
+        assert_eq!(period_start(date, Interval::Day), date);
+        assert_eq!(period_start(date, Interval::Week), date!(2024 - 11 - 11));
+        assert_eq!(
+            period_start(date!(2024 - 11 - 11), Interval::Week),
+            date!(2024 - 11 - 11)
+        );
+        assert_eq!(
+            period_start(date!(2024 - 12 - 01), Interval::Week),
+            date!(2024 - 11 - 25)
+        );
+        assert_eq!(period_start(date, Interval::Month), date!(2024 - 11 - 01));
+    }
+
+    fn observed(series: &BTreeMap<Date, Decimal>) -> BTreeMap<Date, Point> {
+        fill_gaps(
+            series,
+            date!(2024 - 01 - 01),
+            date!(2024 - 12 - 31),
+            Fill::None,
+        )
+    }
+
+    fn rates(resampled: BTreeMap<Date, FilledRate>) -> BTreeMap<Date, PeriodRate> {
+        resampled
+            .into_iter()
+            .map(|(date, rate)| (date, rate.rate))
+            .collect()
+    }
+
+    #[test]
+    fn test_resample() {
+        let series = observed(&BTreeMap::from([
+            (date!(2024 - 10 - 30), dec!(1.0)),
+            (date!(2024 - 10 - 31), dec!(3.0)),
+            (date!(2024 - 11 - 01), dec!(2.0)),
+            (date!(2024 - 11 - 04), dec!(4.0)),
+            (date!(2024 - 11 - 05), dec!(5.0)),
+        ]));
+
+        assert_eq!(
+            resample(&series, Interval::Day, Aggregation::Last).len(),
+            series.len()
+        );
+        assert_eq!(
+            rates(resample(&series, Interval::Week, Aggregation::Last)),
+            BTreeMap::from([
+                (date!(2024 - 10 - 28), PeriodRate::Rate(dec!(2.0))),
+                (date!(2024 - 11 - 04), PeriodRate::Rate(dec!(5.0))),
+            ])
+        );
+        assert_eq!(
+            rates(resample(&series, Interval::Month, Aggregation::Mean)),
+            BTreeMap::from([
+                (date!(2024 - 10 - 01), PeriodRate::Rate(dec!(2.0))),
+                (date!(2024 - 11 - 01), PeriodRate::Rate(dec!(3.6666666667))),
+            ])
+        );
+        let candles = resample(&series, Interval::Week, Aggregation::Ohlc);
+        assert_eq!(
+            candles[&date!(2024 - 10 - 28)],
+            FilledRate {
+                rate: PeriodRate::Ohlc(Ohlc {
+                    open: dec!(1.0),
+                    high: dec!(3.0),
+                    low: dec!(1.0),
+                    close: dec!(2.0)
+                }),
+                filled: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_resample_all() {
+        let periods = resample_all(
+            "CHF",
+            &historical(),
+            (date!(2024 - 11 - 10), date!(2024 - 11 - 12)),
+            Fill::None,
+            Interval::Month,
+            Aggregation::Ohlc,
+        );
+
+        assert_eq!(periods.len(), 1);
+        let november = &periods[&date!(2024 - 11 - 01)];
+        assert_eq!(november.base, "CHF");
+        assert_eq!(
+            november.rates.keys().collect::<Vec<_>>(),
+            vec!["EUR", "JPY", "KES", "USD"]
+        );
+        assert_eq!(
+            november.rates["EUR"],
+            PeriodRate::Ohlc(Ohlc {
+                open: dec!(1.0),
+                high: dec!(1.05),
+                low: dec!(1.0),
+                close: dec!(1.05)
+            })
+        );
+        assert!(november.filled.is_empty());
+    }
+
+    #[test]
+    fn test_resample_all_filled() {
+        let periods = resample_all(
+            "CHF",
+            &historical(),
+            (date!(2024 - 11 - 10), date!(2024 - 11 - 13)),
+            Fill::Previous,
+            Interval::Day,
+            Aggregation::Last,
+        );
+
+        assert_eq!(periods.len(), 4);
+        let day = &periods[&date!(2024 - 11 - 11)];
+        assert_eq!(day.rates["USD"], PeriodRate::Rate(dec!(1.1)));
+        assert_eq!(day.filled, vec!["JPY", "USD"]);
+        // KES is observed on the 11th only
+        let day = &periods[&date!(2024 - 11 - 13)];
+        assert_eq!(day.rates["KES"], PeriodRate::Rate(dec!(150.0)));
+        assert_eq!(day.filled, vec!["EUR", "JPY", "KES", "USD"]);
+        assert!(!periods[&date!(2024 - 11 - 10)].rates.contains_key("KES"));
+    }
+
+    #[test]
+    fn test_overridden_periods() {
+        let mut historical = historical();
+        historical
+            .get_mut(&date!(2024 - 11 - 11))
+            .unwrap()
+            .overridden = vec!["KES".to_string()];
+
+        assert_eq!(
+            overridden_periods(&historical, "KES", Interval::Week),
+            BTreeSet::from([date!(2024 - 11 - 11)])
+        );
+        let periods = resample_all(
+            "CHF",
+            &historical,
+            (date!(2024 - 11 - 10), date!(2024 - 11 - 12)),
+            Fill::None,
+            Interval::Month,
+            Aggregation::Last,
+        );
+        assert_eq!(periods[&date!(2024 - 11 - 01)].overridden, vec!["KES"]);
+        let kes = fluctuations(&historical, FluctuationSort::Counter)
+            .into_iter()
+            .find(|f| f.counter == "KES")
+            .unwrap();
+        assert!(kes.start.overridden);
+        let stats = statistics(
+            "CHF",
+            "EUR",
+            &counter_series(&historical, "EUR"),
+            &overridden_periods(&historical, "EUR", Interval::Day),
+        )
+        .unwrap();
+        assert!(!stats.min.overridden);
+    }
+
+    // ECB has no rates on weekends
+    fn weekdays() -> BTreeMap<Date, Decimal> {
+        BTreeMap::from([
+            (date!(2024 - 11 - 07), dec!(0.5)),
+            (date!(2024 - 11 - 08), dec!(1.0)),
+            (date!(2024 - 11 - 11), dec!(1.6)),
+            (date!(2024 - 11 - 12), dec!(2.0)),
+        ])
+    }
+
+    #[test]
+    fn test_fill_gaps_none() {
+        let points = fill_gaps(
+            &weekdays(),
+            date!(2024 - 11 - 08),
+            date!(2024 - 11 - 12),
+            Fill::None,
+        );
+
+        assert_eq!(points.len(), 4);
+        assert!(points.values().all(|p| !p.filled));
+    }
+
+    #[test]
+    fn test_fill_gaps_previous() {
+        let points = fill_gaps(
+            &weekdays(),
+            date!(2024 - 11 - 06),
+            date!(2024 - 11 - 13),
+            Fill::Previous,
+        );
+
+        // nothing to fill the 6th from
+        assert_eq!(points.keys().next(), Some(&date!(2024 - 11 - 07)));
+        assert_eq!(points.len(), 7);
+        assert_eq!(
+            points[&date!(2024 - 11 - 09)],
+            Point {
+                rate: dec!(1.0),
+                filled: true
+            }
+        );
+        assert_eq!(points[&date!(2024 - 11 - 10)].rate, dec!(1.0));
+        assert!(!points[&date!(2024 - 11 - 11)].filled);
+        assert_eq!(
+            points[&date!(2024 - 11 - 13)],
+            Point {
+                rate: dec!(2.0),
+                filled: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_fill_gaps_linear() {
+        let points = fill_gaps(
+            &weekdays(),
+            date!(2024 - 11 - 07),
+            date!(2024 - 11 - 13),
+            Fill::Linear,
+        );
+
+        // nothing to interpolate the 13th to
+        assert_eq!(points.keys().last(), Some(&date!(2024 - 11 - 12)));
+        assert_eq!(points.len(), 6);
+        let saturday = points[&date!(2024 - 11 - 09)];
+        assert!(saturday.filled);
+        assert_eq!(saturday.rate, dec!(1.2));
+        assert_eq!(points[&date!(2024 - 11 - 10)].rate, dec!(1.4));
+    }
+
+    #[test]
+    fn test_period_rate_json() {
+        assert_eq!(
+            serde_json::to_string(&PeriodRate::Rate(dec!(1.5))).unwrap(),
+            "1.5"
+        );
+        assert_eq!(
+            serde_json::to_string(&PeriodRate::Ohlc(Ohlc {
+                open: dec!(1.0),
+                high: dec!(2.0),
+                low: dec!(0.5),
+                close: dec!(1.5)
+            }))
+            .unwrap(),
+            r#"{"open":1,"high":2,"low":0.5,"close":1.5}"#
+        );
+    }
+}
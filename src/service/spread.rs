@@ -0,0 +1,225 @@
+use crate::route::model::{ExchangeRate, Quote, Side};
+use log::warn;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::env;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+// spreads in percent of the mid rate, the bid and ask are half of the spread below and above the mid
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Spreads {
+    default: Decimal,
+    // by currency, the wider one of the pair applies
+    currencies: HashMap<String, Decimal>,
+    // by client, replaces the spread of the currencies
+    clients: HashMap<String, Decimal>,
+}
+
+static SPREADS: LazyLock<Spreads> = LazyLock::new(|| {
+    Spreads::parse(
+        env::var("SPREAD_PCT").ok().as_deref(),
+        env::var("CURRENCY_SPREADS_PCT").ok().as_deref(),
+        env::var("CLIENT_SPREADS_PCT").ok().as_deref(),
+    )
+});
+
+pub fn spreads() -> &'static Spreads {
+    &SPREADS
+}
+
+impl Spreads {
+    // the lists are given as KES=1.5,UGX=2, invalid entries are skipped
+    pub fn parse(
+        default: Option<&str>,
+        currencies: Option<&str>,
+        clients: Option<&str>,
+    ) -> Spreads {
+        Spreads {
+            default: default
+                .and_then(|v| percent("SPREAD_PCT", v))
+                .unwrap_or_default(),
+            currencies: entries("CURRENCY_SPREADS_PCT", currencies)
+                .map(|(code, pct)| (code.to_uppercase(), pct))
+                .collect(),
+            clients: entries("CLIENT_SPREADS_PCT", clients)
+                .map(|(client, pct)| (client.to_lowercase(), pct))
+                .collect(),
+        }
+    }
+
+    // spread of the pair in percent, the unknown clients get the spread of the currencies
+    pub fn spread_pct(&self, base: &str, counter: &str, client: Option<&str>) -> Decimal {
+        if let Some(pct) = client.and_then(|client| self.clients.get(&client.to_lowercase())) {
+            return *pct;
+        }
+        let base = self.currencies.get(&base.to_uppercase());
+        let counter = self.currencies.get(&counter.to_uppercase());
+        base.max(counter).copied().unwrap_or(self.default)
+    }
+
+    pub fn knows_client(&self, client: &str) -> bool {
+        self.clients.contains_key(&client.to_lowercase())
+    }
+
+    pub fn quote(&self, base: &str, counter: &str, mid: Decimal, client: Option<&str>) -> Quote {
+        quote(base, counter, mid, self.spread_pct(base, counter, client))
+    }
+
+    // the rates of the requested side, the spread is taken pair by pair
+    pub fn price(&self, rates: ExchangeRate, side: Side, client: Option<&str>) -> ExchangeRate {
+        if side == Side::Mid {
+            return rates;
+        }
+        let base = rates.base;
+        let overridden = rates.overridden;
+        let dates = rates.dates;
+        let rates = rates
+            .rates
+            .into_iter()
+            .map(|(counter, mid)| {
+                let price = self.quote(&base, &counter, mid, client).side(side);
+                (counter, price)
+            })
+            .collect();
+        ExchangeRate {
+            base,
+            rates,
+            overridden,
+            dates,
+        }
+    }
+}
+
+fn quote(base: &str, counter: &str, mid: Decimal, spread_pct: Decimal) -> Quote {
+    let half = mid * spread_pct / Decimal::from(200);
+    Quote {
+        base: base.to_string(),
+        counter: counter.to_string(),
+        bid: mid - half,
+        mid,
+        ask: mid + half,
+        spread_pct,
+        overridden: false,
+    }
+}
+
+impl Quote {
+    pub fn side(&self, side: Side) -> Decimal {
+        match side {
+            Side::Bid => self.bid,
+            Side::Mid => self.mid,
+            Side::Ask => self.ask,
+        }
+    }
+}
+
+fn entries<'a>(
+    name: &'static str,
+    list: Option<&'a str>,
+) -> impl Iterator<Item = (&'a str, Decimal)> + 'a {
+    list.unwrap_or_default()
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .filter_map(move |entry| match entry.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                percent(name, value).map(|pct| (key.trim(), pct))
+            }
+            _ => {
+                warn!("{}: invalid entry '{}', expected KEY=percent", name, entry);
+                None
+            }
+        })
+}
+
+fn percent(name: &str, value: &str) -> Option<Decimal> {
+    match Decimal::from_str(value.trim()) {
+        Ok(pct) if !pct.is_sign_negative() && pct < Decimal::ONE_HUNDRED => Some(pct),
+        _ => {
+            warn!(
+                "{}: invalid spread '{}', expected percent from 0 to 100",
+                name, value
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn configured() -> Spreads {
+        Spreads::parse(
+            Some("0.5"),
+            Some("KES=1.5, ugx=2,XYZ=abc,=1"),
+            Some("cooperaid=1.0,velocorner=0"),
+        )
+    }
+
+    #[test]
+    fn test_parse() {
+        let spreads = configured();
+
+        assert_eq!(spreads.default, dec!(0.5));
+        assert_eq!(
+            spreads.currencies,
+            HashMap::from([("KES".to_string(), dec!(1.5)), ("UGX".to_string(), dec!(2))])
+        );
+        assert_eq!(spreads.clients.len(), 2);
+        assert_eq!(Spreads::parse(None, None, None), Spreads::default());
+        assert_eq!(Spreads::parse(Some("-1"), None, None).default, dec!(0));
+    }
+
+    #[test]
+    fn test_spread_pct() {
+        let spreads = configured();
+
+        assert_eq!(spreads.spread_pct("CHF", "EUR", None), dec!(0.5));
+        assert_eq!(spreads.spread_pct("CHF", "KES", None), dec!(1.5));
+        assert_eq!(spreads.spread_pct("UGX", "KES", None), dec!(2));
+        assert_eq!(
+            spreads.spread_pct("CHF", "UGX", Some("CoopERAID")),
+            dec!(1.0)
+        );
+        assert_eq!(
+            spreads.spread_pct("CHF", "UGX", Some("velocorner")),
+            dec!(0)
+        );
+        assert!(spreads.knows_client("Velocorner"));
+        assert!(!spreads.knows_client("unknown"));
+    }
+
+    #[test]
+    fn test_quote() {
+        let quote = quote("CHF", "KES", dec!(146.20), dec!(1.5));
+
+        assert_eq!(quote.bid, dec!(145.1035));
+        assert_eq!(quote.mid, dec!(146.20));
+        assert_eq!(quote.ask, dec!(147.2965));
+        assert_eq!(quote.side(Side::Ask), quote.ask);
+    }
+
+    #[test]
+    fn test_price() {
+        let rates = ExchangeRate {
+            base: "CHF".to_string(),
+            overridden: Vec::new(),
+            dates: HashMap::new(),
+            rates: HashMap::from([
+                ("EUR".to_string(), dec!(1.0)),
+                ("KES".to_string(), dec!(146.2)),
+            ]),
+        };
+        let spreads = configured();
+
+        let bid = spreads.price(rates.clone(), Side::Bid, None);
+        assert_eq!(bid.rates["EUR"], dec!(0.9975));
+        assert_eq!(bid.rates["KES"], dec!(145.1035));
+        let ask = spreads.price(rates.clone(), Side::Ask, Some("cooperaid"));
+        assert_eq!(ask.rates["EUR"], dec!(1.005));
+        let mid = spreads.price(rates.clone(), Side::Mid, Some("cooperaid"));
+        assert_eq!(mid.rates, rates.rates);
+    }
+}
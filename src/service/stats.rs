@@ -0,0 +1,165 @@
+use crate::route::model::{LatencyPercentiles, ProviderFailure, QuotaStatus};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+// keeps the latest samples only, enough for percentiles without growing unbounded
+const MAX_SAMPLES: usize = 100;
+
+// call statistics of a single provider, reported on the status endpoint
+#[derive(Default)]
+pub struct ProviderStats {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    last_success: Option<OffsetDateTime>,
+    last_error: Option<ProviderFailure>,
+    latencies: VecDeque<Duration>,
+    currencies: Option<usize>,
+    rate_limited_since: Option<OffsetDateTime>,
+}
+
+pub struct StatsSnapshot {
+    pub last_success: Option<OffsetDateTime>,
+    pub last_error: Option<ProviderFailure>,
+    pub latency: LatencyPercentiles,
+    pub currencies: Option<usize>,
+    pub quota: QuotaStatus,
+}
+
+impl ProviderStats {
+    pub fn record_success(&self, latency: Duration, currencies: Option<usize>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.push_latency(latency);
+        inner.last_success = Some(OffsetDateTime::now_utc());
+        inner.rate_limited_since = None;
+        if currencies.is_some() {
+            inner.currencies = currencies;
+        }
+    }
+
+    pub fn record_failure(&self, latency: Duration, message: String, rate_limited: bool) {
+        let mut inner = self.inner.lock().unwrap();
+        let now = OffsetDateTime::now_utc();
+        inner.push_latency(latency);
+        inner.last_error = Some(ProviderFailure { message, at: now });
+        if rate_limited {
+            inner.rate_limited_since.get_or_insert(now);
+        }
+    }
+
+    pub fn last_success(&self) -> Option<OffsetDateTime> {
+        self.inner.lock().unwrap().last_success
+    }
+
+    pub fn snapshot(&self) -> StatsSnapshot {
+        let inner = self.inner.lock().unwrap();
+        StatsSnapshot {
+            last_success: inner.last_success,
+            last_error: inner.last_error.clone(),
+            latency: percentiles(&inner.latencies),
+            currencies: inner.currencies,
+            quota: QuotaStatus {
+                rate_limited: inner.rate_limited_since.is_some(),
+                since: inner.rate_limited_since,
+            },
+        }
+    }
+}
+
+impl Inner {
+    fn push_latency(&mut self, latency: Duration) {
+        if self.latencies.len() == MAX_SAMPLES {
+            self.latencies.pop_front();
+        }
+        self.latencies.push_back(latency);
+    }
+}
+
+fn percentiles(samples: &VecDeque<Duration>) -> LatencyPercentiles {
+    let mut sorted = samples.iter().copied().collect::<Vec<_>>();
+    sorted.sort();
+    LatencyPercentiles {
+        samples: sorted.len(),
+        p50_ms: nearest_rank(&sorted, 50),
+        p90_ms: nearest_rank(&sorted, 90),
+        p99_ms: nearest_rank(&sorted, 99),
+    }
+}
+
+// nearest-rank percentile in milliseconds, none without samples
+fn nearest_rank(sorted: &[Duration], percentile: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (percentile * sorted.len()).div_ceil(100).max(1);
+    Some(sorted[rank - 1].as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles() {
+        let samples = (1..=100)
+            .map(Duration::from_millis)
+            .collect::<VecDeque<_>>();
+
+        let latency = percentiles(&samples);
+
+        assert_eq!(latency.samples, 100);
+        assert_eq!(latency.p50_ms, Some(50));
+        assert_eq!(latency.p90_ms, Some(90));
+        assert_eq!(latency.p99_ms, Some(99));
+    }
+
+    #[test]
+    fn test_percentiles_without_samples() {
+        let latency = percentiles(&VecDeque::new());
+
+        assert_eq!(latency.samples, 0);
+        assert_eq!(latency.p50_ms, None);
+        assert_eq!(latency.p99_ms, None);
+    }
+
+    #[test]
+    fn test_keeps_latest_samples_only() {
+        let stats = ProviderStats::default();
+        for ms in 0..(MAX_SAMPLES as u64 + 50) {
+            stats.record_success(Duration::from_millis(ms), None);
+        }
+
+        let latency = stats.snapshot().latency;
+
+        assert_eq!(latency.samples, MAX_SAMPLES);
+        assert_eq!(latency.p50_ms, Some(99));
+    }
+
+    #[test]
+    fn test_records_success_and_failure() {
+        let stats = ProviderStats::default();
+
+        stats.record_failure(Duration::from_millis(10), "timeout".to_string(), false);
+        let snapshot = stats.snapshot();
+        assert!(snapshot.last_success.is_none());
+        assert_eq!(snapshot.last_error.unwrap().message, "timeout");
+        assert!(!snapshot.quota.rate_limited);
+
+        stats.record_failure(Duration::from_millis(10), "429".to_string(), true);
+        assert!(stats.snapshot().quota.rate_limited);
+
+        stats.record_success(Duration::from_millis(20), Some(31));
+        stats.record_success(Duration::from_millis(20), None);
+        let snapshot = stats.snapshot();
+        assert!(snapshot.last_success.is_some());
+        // the last error is kept for the on-call
+        assert_eq!(snapshot.last_error.unwrap().message, "429");
+        assert_eq!(snapshot.currencies, Some(31));
+        assert!(!snapshot.quota.rate_limited);
+        assert!(snapshot.quota.since.is_none());
+    }
+}
@@ -0,0 +1,92 @@
+use log::{error, info};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use std::env;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+const SERVICE_NAME: &str = "exchange-rate-service";
+
+// OTLP/HTTP endpoint of the collector, the signal path is appended
+fn traces_endpoint() -> String {
+    let endpoint = env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .unwrap_or_else(|_| "http://localhost:4318".to_string());
+    format!("{}/v1/traces", endpoint.trim_end_matches('/'))
+}
+
+fn tracer_provider(endpoint: &str) -> Option<SdkTracerProvider> {
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(|e| error!("failed to create the OTLP exporter for {}: {}", endpoint, e))
+        .ok()?;
+    Some(
+        SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+            .build(),
+    )
+}
+
+// exports the tracing spans of the routes, the cache and the providers,
+// the returned provider must be shut down to flush the pending spans
+pub fn init() -> Option<SdkTracerProvider> {
+    let endpoint = traces_endpoint();
+    let provider = tracer_provider(&endpoint)?;
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME));
+    if let Err(e) = tracing_subscriber::registry().with(layer).try_init() {
+        error!("failed to install the tracing subscriber: {}", e);
+        return None;
+    }
+    info!("exporting traces to {}", endpoint);
+    Some(provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{Tracer, TracerProvider};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_traces_endpoint() {
+        // the variable is not set in tests
+        assert_eq!(traces_endpoint(), "http://localhost:4318/v1/traces");
+    }
+
+    #[test]
+    fn test_exports_spans_to_collector() {
+        // collector stub, accepts a single export request
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/v1/traces", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 8192];
+            let read = stream.read(&mut buf).unwrap();
+            tx.send(String::from_utf8_lossy(&buf[..read]).to_string())
+                .unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .unwrap();
+        });
+        let provider = tracer_provider(&endpoint).unwrap();
+
+        provider
+            .tracer(SERVICE_NAME)
+            .in_span("GET /api/rates/{base}", |_| {});
+        provider.force_flush().unwrap();
+
+        let request = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(request.starts_with("POST /v1/traces"));
+        assert!(request.contains("application/x-protobuf"));
+        provider.shutdown().unwrap();
+    }
+}